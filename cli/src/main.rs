@@ -0,0 +1,670 @@
+//! `solami-admin`: day-to-day operations for a deployed `my_solami_token` mint.
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use my_solami_token_client::{accounts, instructions, pda};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    transaction::Transaction,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "solami-admin",
+    version,
+    about = "Admin CLI for the my_solami_token program"
+)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        short = 'u',
+        default_value = "http://127.0.0.1:8899",
+        global = true
+    )]
+    url: String,
+    /// Admin keypair file, defaults to ~/.config/solana/id.json
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<String>,
+    /// Token mint the program state is seeded by
+    #[arg(long, short = 'm', global = true)]
+    mint: Option<Pubkey>,
+    /// Token program owning the mint (SPL Token or Token-2022)
+    #[arg(long, default_value_t = anchor_spl::token::ID, global = true)]
+    token_program: Pubkey,
+    /// Simulate and print the instruction without sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the decoded `TokenState` and whitelist
+    Show,
+    /// Print the registered holder count and the largest token accounts
+    Holders,
+    /// Initialize the token state and distribute the initial supply
+    Initialize {
+        #[arg(long)]
+        total_supply: u64,
+        #[arg(long)]
+        admin_token_account: Pubkey,
+        #[arg(long)]
+        lp_pool: Pubkey,
+        #[arg(long)]
+        rewards_pool: Pubkey,
+        #[arg(long)]
+        lp_fund: Pubkey,
+        #[arg(long)]
+        staking_pool: Pubkey,
+        #[arg(long)]
+        swap_wallet: Pubkey,
+        #[arg(long)]
+        token_account: Pubkey,
+        /// Whitelisted wallets receiving the whitelist allocation
+        #[arg(long = "whitelist", num_args = 1..)]
+        whitelist_wallets: Vec<Pubkey>,
+        /// Tokens escrowed for the presale, taken from the marketing allocation
+        #[arg(long, default_value_t = 0)]
+        presale_tranche: u64,
+        /// Token account owned by the presale PDA, required with `--presale-tranche`
+        #[arg(long)]
+        presale_escrow: Option<Pubkey>,
+        /// Token account owned by the bonding curve PDA, seeded instead of `--lp-pool`
+        #[arg(long)]
+        curve_reserve: Option<Pubkey>,
+    },
+    /// Manage the whitelist
+    Whitelist {
+        #[command(subcommand)]
+        action: WhitelistAction,
+    },
+    /// Freeze the contract
+    Freeze,
+    /// Unfreeze the contract
+    Unfreeze,
+    /// Burn tokens from the admin token account
+    Burn {
+        amount: u64,
+        #[arg(long)]
+        admin_token_account: Pubkey,
+    },
+    /// Move rewards pool tokens to the swap wallet
+    SwapRewards { amount: u64 },
+    /// Refresh the TVL balances recorded on `TokenState`
+    RefreshTvl,
+    /// Point one of the pools stored on `TokenState` at another token account
+    SetPool { pool: Pool, address: Pubkey },
+    /// Set the launch phase schedule (unix timestamps)
+    LaunchPhases {
+        #[arg(long)]
+        whitelist_start: i64,
+        #[arg(long)]
+        limited_start: i64,
+        #[arg(long)]
+        public_start: i64,
+        /// Largest transfer allowed during the limited phase
+        #[arg(long)]
+        limited_max_transfer: u64,
+    },
+    /// Set the anti-sniper launch tax, decaying to the base rate after `launch_time`
+    LaunchTax {
+        /// Tax rate at launch in basis points
+        #[arg(long)]
+        start_bps: u16,
+        /// Seconds until the rate reaches the base tax
+        #[arg(long)]
+        window: i64,
+        /// Number of equal steps, 0 for a linear decay
+        #[arg(long, default_value_t = 0)]
+        steps: u8,
+    },
+    /// Configure or settle the presale
+    Presale {
+        #[command(subcommand)]
+        action: PresaleAction,
+    },
+    /// Configure the bonding curve over the tokens held by `curve_reserve`
+    Curve {
+        #[arg(long, value_enum)]
+        kind: Curve,
+        /// Lamports per 1e9 token base units at the start of the curve
+        #[arg(long)]
+        start_price: u64,
+        /// Price increase per step: lamports for linear, basis points for exponential
+        #[arg(long)]
+        growth: u64,
+        /// Tokens sold between price steps
+        #[arg(long)]
+        step_size: u64,
+        /// Market cap in lamports at which the reserve migrates to the LP
+        #[arg(long)]
+        migration_market_cap: u64,
+    },
+    /// Set the share of the rewards tax credited to referrers
+    Referrals {
+        /// Basis points of the rewards share
+        #[arg(long)]
+        bps: u16,
+        /// Token account owned by the mint authority PDA holding unclaimed rewards
+        #[arg(long)]
+        vault: Pubkey,
+    },
+    /// Replace the holder loyalty tiers; no tiers turns the discounts off
+    LoyaltyTiers {
+        /// `MIN_HOLD_SECS:MIN_BALANCE:DISCOUNT_BPS`, repeatable
+        #[arg(long = "tier", value_parser = parse_loyalty_tier)]
+        tiers: Vec<accounts::LoyaltyTier>,
+    },
+    /// Configure token-weighted governance; hand it the admin role with
+    /// `transfer-ownership` to the governance PDA
+    Governance {
+        /// Seconds each proposal stays open for votes
+        #[arg(long)]
+        voting_period: i64,
+        /// Minimum votes cast for a proposal to pass
+        #[arg(long)]
+        quorum: u64,
+        /// Minimum balance needed to create a proposal
+        #[arg(long, default_value_t = 0)]
+        proposal_threshold: u64,
+    },
+    /// Record a balance snapshot at the current slot
+    Snapshot,
+    /// Hand the admin role to a new wallet
+    TransferOwnership { new_admin: Pubkey },
+    /// Compare `TokenState` against the mint and pools, optionally correcting it
+    Reconcile {
+        #[arg(long)]
+        correct: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Pool {
+    LpPool,
+    RewardsPool,
+    LpFund,
+    StakingPool,
+    SwapWallet,
+}
+
+impl From<Pool> for accounts::PoolKind {
+    fn from(pool: Pool) -> Self {
+        match pool {
+            Pool::LpPool => accounts::PoolKind::LpPool,
+            Pool::RewardsPool => accounts::PoolKind::RewardsPool,
+            Pool::LpFund => accounts::PoolKind::LpFund,
+            Pool::StakingPool => accounts::PoolKind::StakingPool,
+            Pool::SwapWallet => accounts::PoolKind::SwapWallet,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Curve {
+    Linear,
+    Exponential,
+}
+
+impl From<Curve> for accounts::CurveKind {
+    fn from(curve: Curve) -> Self {
+        match curve {
+            Curve::Linear => accounts::CurveKind::Linear,
+            Curve::Exponential => accounts::CurveKind::Exponential,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum WhitelistAction {
+    Add { wallet: Pubkey },
+    Remove { wallet: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum PresaleAction {
+    /// Open the presale; caps are in lamports, times are unix timestamps
+    Configure {
+        /// Token base units sold per SOL
+        #[arg(long)]
+        tokens_per_sol: u64,
+        #[arg(long)]
+        soft_cap: u64,
+        #[arg(long)]
+        hard_cap: u64,
+        #[arg(long)]
+        wallet_cap: u64,
+        #[arg(long)]
+        start_time: i64,
+        #[arg(long)]
+        end_time: i64,
+    },
+    /// Take the proceeds and unsold tokens once the presale has ended
+    Finalize {
+        #[arg(long)]
+        admin_token_account: Pubkey,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed());
+    let mint = cli.mint.ok_or_else(|| anyhow!("--mint is required"))?;
+
+    match cli.command {
+        Command::Show => return show(&rpc, &mint),
+        Command::Holders => return holders(&rpc, &mint),
+        _ => {}
+    }
+
+    let admin = load_keypair(cli.keypair.as_deref())?;
+    let admin_key = admin.pubkey();
+    let ix = match cli.command {
+        Command::Show | Command::Holders => unreachable!(),
+        Command::Initialize {
+            total_supply,
+            admin_token_account,
+            lp_pool,
+            rewards_pool,
+            lp_fund,
+            staking_pool,
+            swap_wallet,
+            token_account,
+            whitelist_wallets,
+            presale_tranche,
+            presale_escrow,
+            curve_reserve,
+        } => instructions::initialize_token(
+            &instructions::InitializeTokenAccounts {
+                mint,
+                admin: admin_key,
+                admin_token_account,
+                lp_pool,
+                rewards_pool,
+                lp_fund,
+                staking_pool,
+                swap_wallet,
+                token_account,
+                presale_escrow,
+                curve_reserve,
+                token_program: cli.token_program,
+            },
+            total_supply,
+            whitelist_wallets,
+            None,
+            presale_tranche,
+        ),
+        Command::Whitelist { action } => match action {
+            WhitelistAction::Add { wallet } => {
+                instructions::add_to_whitelist(&mint, &admin_key, wallet)
+            }
+            WhitelistAction::Remove { wallet } => {
+                instructions::remove_from_whitelist(&mint, &admin_key, wallet)
+            }
+        },
+        Command::Freeze => instructions::freeze_contract(&mint, &admin_key, true),
+        Command::Unfreeze => instructions::freeze_contract(&mint, &admin_key, false),
+        Command::Burn {
+            amount,
+            admin_token_account,
+        } => instructions::manual_burn(
+            &mint,
+            &admin_key,
+            &admin_token_account,
+            &cli.token_program,
+            holder_rent_payer(&rpc, &mint, &admin_token_account),
+            amount,
+        ),
+        Command::SwapRewards { amount } => {
+            let state = fetch_token_state(&rpc, &mint)?;
+            instructions::prepare_rewards_swap(
+                &mint,
+                &admin_key,
+                &state.rewards_pool,
+                &state.swap_wallet,
+                &cli.token_program,
+                amount,
+            )
+        }
+        Command::RefreshTvl => {
+            let state = fetch_token_state(&rpc, &mint)?;
+            instructions::update_tvl_data(
+                &mint,
+                &admin_key,
+                &state.lp_pool,
+                &state.rewards_pool,
+                &state.staking_pool,
+            )
+        }
+        Command::SetPool { pool, address } => {
+            instructions::set_pool_address(&mint, &admin_key, pool.into(), &address)
+        }
+        Command::LaunchPhases {
+            whitelist_start,
+            limited_start,
+            public_start,
+            limited_max_transfer,
+        } => instructions::configure_launch_phases(
+            &mint,
+            &admin_key,
+            whitelist_start,
+            limited_start,
+            public_start,
+            limited_max_transfer,
+        ),
+        Command::LaunchTax {
+            start_bps,
+            window,
+            steps,
+        } => instructions::configure_launch_tax(&mint, &admin_key, start_bps, window, steps),
+        Command::Presale { action } => match action {
+            PresaleAction::Configure {
+                tokens_per_sol,
+                soft_cap,
+                hard_cap,
+                wallet_cap,
+                start_time,
+                end_time,
+            } => instructions::configure_presale(
+                &mint,
+                &admin_key,
+                tokens_per_sol,
+                soft_cap,
+                hard_cap,
+                wallet_cap,
+                start_time,
+                end_time,
+            ),
+            PresaleAction::Finalize {
+                admin_token_account,
+            } => {
+                let state = fetch_token_state(&rpc, &mint)?;
+                instructions::finalize_presale(
+                    &mint,
+                    &admin_key,
+                    &admin_token_account,
+                    &state.presale_escrow,
+                    &cli.token_program,
+                )
+            }
+        },
+        Command::Curve {
+            kind,
+            start_price,
+            growth,
+            step_size,
+            migration_market_cap,
+        } => {
+            let state = fetch_token_state(&rpc, &mint)?;
+            instructions::configure_bonding_curve(
+                &mint,
+                &admin_key,
+                &state.curve_reserve,
+                accounts::CurveShape {
+                    kind: kind.into(),
+                    start_price,
+                    growth,
+                    step_size,
+                },
+                migration_market_cap,
+            )
+        }
+        Command::Referrals { bps, vault } => {
+            instructions::configure_referrals(&mint, &admin_key, &vault, bps)
+        }
+        Command::LoyaltyTiers { tiers } => {
+            instructions::configure_loyalty_tiers(&mint, &admin_key, tiers)
+        }
+        Command::Governance {
+            voting_period,
+            quorum,
+            proposal_threshold,
+        } => instructions::configure_governance(
+            &mint,
+            &admin_key,
+            voting_period,
+            quorum,
+            proposal_threshold,
+        ),
+        Command::Snapshot => {
+            let state = fetch_token_state(&rpc, &mint)?;
+            instructions::take_snapshot(&mint, &admin_key, state.snapshot_id + 1)
+        }
+        Command::TransferOwnership { new_admin } => {
+            instructions::transfer_ownership(&mint, &admin_key, new_admin)
+        }
+        Command::Reconcile { correct } => {
+            let state = fetch_token_state(&rpc, &mint)?;
+            instructions::reconcile_supply(
+                &mint,
+                &admin_key,
+                &state.lp_pool,
+                &state.rewards_pool,
+                &state.staking_pool,
+                correct,
+            )
+        }
+    };
+
+    let blockhash = rpc.get_latest_blockhash()?;
+    let tx =
+        Transaction::new_signed_with_payer(&[ix.clone()], Some(&admin_key), &[&admin], blockhash);
+
+    if cli.dry_run {
+        print_instruction(&ix);
+        let result = rpc.simulate_transaction(&tx)?.value;
+        for line in result.logs.unwrap_or_default() {
+            println!("  {line}");
+        }
+        if let Some(units) = result.units_consumed {
+            println!("compute units: {units}");
+        }
+        return match result.err {
+            Some(err) => Err(anyhow!("simulation failed: {err}")),
+            None => Ok(()),
+        };
+    }
+
+    let signature = rpc.send_and_confirm_transaction(&tx)?;
+    println!("signature: {signature}");
+    show(&rpc, &mint)
+}
+
+/// Load the signing keypair, falling back to the Solana CLI default path
+fn load_keypair(path: Option<&str>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path.to_string(),
+        None => format!("{}/.config/solana/id.json", std::env::var("HOME")?),
+    };
+    read_keypair_file(&path).map_err(|err| anyhow!("failed to read keypair {path}: {err}"))
+}
+
+/// Parse a `MIN_HOLD_SECS:MIN_BALANCE:DISCOUNT_BPS` loyalty tier
+fn parse_loyalty_tier(value: &str) -> Result<accounts::LoyaltyTier> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [min_hold, min_balance, discount_bps] = parts[..] else {
+        return Err(anyhow!(
+            "expected MIN_HOLD_SECS:MIN_BALANCE:DISCOUNT_BPS, got {value}"
+        ));
+    };
+    Ok(accounts::LoyaltyTier {
+        min_hold: min_hold.parse()?,
+        min_balance: min_balance.parse()?,
+        discount_bps: discount_bps.parse()?,
+    })
+}
+
+/// Print the program id, account metas and data of an instruction
+fn print_instruction(ix: &Instruction) {
+    println!("program: {}", ix.program_id);
+    println!("accounts:");
+    for (i, meta) in ix.accounts.iter().enumerate() {
+        println!(
+            "  #{i:<2} {} {}{}",
+            meta.pubkey,
+            if meta.is_writable { "w" } else { "-" },
+            if meta.is_signer { "s" } else { "-" },
+        );
+    }
+    let data: String = ix.data.iter().map(|b| format!("{b:02x}")).collect();
+    println!("data: {data}");
+}
+
+/// Fetch and decode the `TokenState` of a mint
+fn fetch_token_state(rpc: &RpcClient, mint: &Pubkey) -> Result<accounts::TokenState> {
+    let (token_state_key, _) = pda::token_state(mint);
+    let data = rpc
+        .get_account_data(&token_state_key)
+        .with_context(|| format!("token state {token_state_key} not found"))?;
+    Ok(accounts::decode_token_state(&data)?)
+}
+
+/// `rent_payer` of the `HolderRecord` of `token_account`, if it has one
+fn holder_rent_payer(rpc: &RpcClient, mint: &Pubkey, token_account: &Pubkey) -> Option<Pubkey> {
+    let (record_key, _) = pda::holder_record(mint, token_account);
+    let data = rpc.get_account_data(&record_key).ok()?;
+    accounts::decode_holder_record(&data)
+        .ok()
+        .map(|record| record.rent_payer)
+}
+
+/// Fetch and print the decoded `TokenState` and whitelist for a mint
+fn show(rpc: &RpcClient, mint: &Pubkey) -> Result<()> {
+    let (token_state_key, _) = pda::token_state(mint);
+    let state = fetch_token_state(rpc, mint)?;
+
+    println!("token_state: {token_state_key}");
+    println!("  admin:                  {}", state.admin);
+    println!("  total_supply:           {}", state.total_supply);
+    println!("  total_burned:           {}", state.total_burned);
+    println!("  total_tax_collected:    {}", state.total_tax_collected);
+    println!("  total_transactions:     {}", state.total_transactions);
+    println!("  launch_time:            {}", state.launch_time);
+    println!(
+        "  reward_start_time:      {}",
+        state.reward_distribution_start_time
+    );
+    println!("  launch_phase:           {:?}", state.launch_phase);
+    println!("  whitelist_phase_start:  {}", state.whitelist_phase_start);
+    println!("  limited_phase_start:    {}", state.limited_phase_start);
+    println!("  public_phase_start:     {}", state.public_phase_start);
+    println!("  limited_max_transfer:   {}", state.limited_max_transfer);
+    println!(
+        "  launch_tax:             {} bps over {}s ({} steps)",
+        state.launch_tax_bps, state.launch_tax_window, state.launch_tax_steps
+    );
+    println!("  is_frozen:              {}", state.is_frozen);
+    println!("  supply_finalized:       {}", state.supply_finalized);
+    println!("  tax_backend:            {:?}", state.tax_backend);
+    println!("  liquidity_pool_balance: {}", state.liquidity_pool_balance);
+    println!("  rewards_pool_balance:   {}", state.rewards_pool_balance);
+    println!("  staking_pool_balance:   {}", state.staking_pool_balance);
+    println!("  holder_count:           {}", state.holder_count);
+    println!(
+        "  referrals:              {} bps of rewards, vault {}",
+        state.referral_bps, state.referral_vault
+    );
+    println!("  loyalty_min_balance:    {}", state.loyalty_min_balance);
+    println!("  loyalty_since:          {}", state.loyalty_since);
+    println!("  snapshot_id:            {}", state.snapshot_id);
+    println!("  lp_pool:                {}", state.lp_pool);
+    println!("  rewards_pool:           {}", state.rewards_pool);
+    println!("  lp_fund:                {}", state.lp_fund);
+    println!("  staking_pool:           {}", state.staking_pool);
+    println!("  swap_wallet:            {}", state.swap_wallet);
+
+    let (presale_key, _) = pda::presale(mint);
+    if let Ok(data) = rpc.get_account_data(&presale_key) {
+        let presale = accounts::decode_presale(&data)?;
+        println!("presale: {presale_key}");
+        println!("  escrow:                 {}", state.presale_escrow);
+        println!("  tranche:                {}", state.presale_tranche);
+        println!("  tokens_per_sol:         {}", presale.tokens_per_sol);
+        println!(
+            "  caps (lamports):        soft {} / hard {} / wallet {}",
+            presale.soft_cap, presale.hard_cap, presale.wallet_cap
+        );
+        println!(
+            "  window:                 {} - {}",
+            presale.start_time, presale.end_time
+        );
+        println!("  total_raised:           {}", presale.total_raised);
+        println!("  contributors:           {}", presale.contributors);
+        println!("  finalized:              {}", presale.finalized);
+    }
+
+    let (curve_key, _) = pda::bonding_curve(mint);
+    if let Ok(data) = rpc.get_account_data(&curve_key) {
+        let curve = accounts::decode_bonding_curve(&data)?;
+        println!("bonding_curve: {curve_key}");
+        println!("  reserve:                {}", state.curve_reserve);
+        println!(
+            "  shape:                  {:?} from {} step {} every {}",
+            curve.shape.kind, curve.shape.start_price, curve.shape.growth, curve.shape.step_size
+        );
+        println!(
+            "  tokens_sold:            {} / {}",
+            curve.tokens_sold, curve.curve_supply
+        );
+        println!("  sol_reserve:            {}", curve.sol_reserve);
+        println!("  migration_market_cap:   {}", curve.migration_market_cap);
+        println!("  migrated:               {}", curve.migrated);
+    }
+
+    let (loyalty_key, _) = pda::loyalty_tiers(mint);
+    if let Ok(data) = rpc.get_account_data(&loyalty_key) {
+        let loyalty = accounts::decode_loyalty_tiers(&data)?;
+        println!("loyalty_tiers: {loyalty_key}");
+        for tier in &loyalty.tiers {
+            println!(
+                "  {} bps off after {}s holding {}",
+                tier.discount_bps, tier.min_hold, tier.min_balance
+            );
+        }
+    }
+
+    let (governance_key, _) = pda::governance(mint);
+    if let Ok(data) = rpc.get_account_data(&governance_key) {
+        let governance = accounts::decode_governance(&data)?;
+        println!("governance: {governance_key}");
+        println!(
+            "  is_admin:               {}",
+            state.admin == governance_key
+        );
+        println!("  voting_period:          {}s", governance.voting_period);
+        println!("  quorum:                 {}", governance.quorum);
+        println!(
+            "  proposal_threshold:     {}",
+            governance.proposal_threshold
+        );
+        println!("  proposal_count:         {}", governance.proposal_count);
+    }
+
+    let (whitelist_key, _) = pda::whitelist(mint);
+    if let Ok(data) = rpc.get_account_data(&whitelist_key) {
+        let whitelist = accounts::decode_whitelist(&data)?;
+        println!("whitelist: {whitelist_key}");
+        for wallet in whitelist.wallets {
+            println!("  {wallet}");
+        }
+    }
+    Ok(())
+}
+
+/// Print the registered holder count and the largest token accounts of a mint
+fn holders(rpc: &RpcClient, mint: &Pubkey) -> Result<()> {
+    let state = fetch_token_state(rpc, mint)?;
+    println!("holder_count: {}", state.holder_count);
+    println!("largest accounts:");
+    for account in rpc.get_token_largest_accounts(mint)? {
+        println!("  {} {}", account.address, account.amount.ui_amount_string);
+    }
+    Ok(())
+}
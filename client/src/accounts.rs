@@ -0,0 +1,83 @@
+use anchor_lang::{AccountDeserialize, Result};
+
+pub use my_solami_token::state::{
+    BalanceCheckpoints, BondingCurve, Checkpoint, ConfidentialConfig, CurveKind, CurveShape,
+    Governance, HolderRecord, LaunchPhase, LoyaltyTier, LoyaltyTiers, PoolKind, Presale,
+    PresaleReceipt, Proposal, ProposalAction, ReferralRecord, ReferrerStats, Snapshot, TaxBackend,
+    TokenState, VoteRecord, Whitelist,
+};
+
+/// Decode a `TokenState` account, checking its discriminator
+pub fn decode_token_state(data: &[u8]) -> Result<TokenState> {
+    TokenState::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Whitelist` account, checking its discriminator
+pub fn decode_whitelist(data: &[u8]) -> Result<Whitelist> {
+    Whitelist::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `HolderRecord` account, checking its discriminator
+pub fn decode_holder_record(data: &[u8]) -> Result<HolderRecord> {
+    HolderRecord::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Presale` account, checking its discriminator
+pub fn decode_presale(data: &[u8]) -> Result<Presale> {
+    Presale::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `PresaleReceipt` account, checking its discriminator
+pub fn decode_presale_receipt(data: &[u8]) -> Result<PresaleReceipt> {
+    PresaleReceipt::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `BondingCurve` account, checking its discriminator
+pub fn decode_bonding_curve(data: &[u8]) -> Result<BondingCurve> {
+    BondingCurve::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `ReferralRecord` account, checking its discriminator
+pub fn decode_referral_record(data: &[u8]) -> Result<ReferralRecord> {
+    ReferralRecord::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `ReferrerStats` account, checking its discriminator
+pub fn decode_referrer_stats(data: &[u8]) -> Result<ReferrerStats> {
+    ReferrerStats::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `LoyaltyTiers` account, checking its discriminator
+pub fn decode_loyalty_tiers(data: &[u8]) -> Result<LoyaltyTiers> {
+    LoyaltyTiers::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Governance` account, checking its discriminator
+pub fn decode_governance(data: &[u8]) -> Result<Governance> {
+    Governance::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Proposal` account, checking its discriminator
+pub fn decode_proposal(data: &[u8]) -> Result<Proposal> {
+    Proposal::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `VoteRecord` account, checking its discriminator
+pub fn decode_vote_record(data: &[u8]) -> Result<VoteRecord> {
+    VoteRecord::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `Snapshot` account, checking its discriminator
+pub fn decode_snapshot(data: &[u8]) -> Result<Snapshot> {
+    Snapshot::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `BalanceCheckpoints` account, checking its discriminator
+pub fn decode_balance_checkpoints(data: &[u8]) -> Result<BalanceCheckpoints> {
+    BalanceCheckpoints::try_deserialize(&mut &data[..])
+}
+
+/// Decode a `ConfidentialConfig` account, checking its discriminator
+pub fn decode_confidential_config(data: &[u8]) -> Result<ConfidentialConfig> {
+    ConfidentialConfig::try_deserialize(&mut &data[..])
+}
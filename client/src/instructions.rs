@@ -0,0 +1,1206 @@
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    system_program, sysvar,
+};
+use anchor_lang::{prelude::Pubkey, InstructionData, ToAccountMetas};
+use my_solami_token::{
+    accounts, instruction,
+    state::{
+        BatchTransferItem, CurveShape, LoyaltyTier, PoolKind, ProposalAction, TokenState,
+        TransferFeeParams,
+    },
+    ID,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Accounts for [`initialize_token`]
+pub struct InitializeTokenAccounts {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub admin_token_account: Pubkey,
+    pub lp_pool: Pubkey,
+    pub rewards_pool: Pubkey,
+    pub lp_fund: Pubkey,
+    pub staking_pool: Pubkey,
+    pub swap_wallet: Pubkey,
+    pub token_account: Pubkey,
+    /// Token account owned by [`pda::presale`], required with a presale tranche
+    pub presale_escrow: Option<Pubkey>,
+    /// Token account owned by [`pda::bonding_curve`]; receives the LP share in fair launch mode
+    pub curve_reserve: Option<Pubkey>,
+    pub token_program: Pubkey,
+}
+
+/// `initialize_token`; each whitelisted wallet's ATA is appended as a remaining account
+pub fn initialize_token(
+    keys: &InitializeTokenAccounts,
+    total_supply: u64,
+    whitelist_wallets: Vec<Pubkey>,
+    transfer_fee: Option<TransferFeeParams>,
+    presale_tranche: u64,
+) -> Instruction {
+    let mut ix = build(
+        accounts::InitializeToken {
+            token_state: pda::token_state(&keys.mint).0,
+            whitelist: pda::whitelist(&keys.mint).0,
+            mint_authority: pda::mint_authority(&keys.mint).0,
+            mint: keys.mint,
+            admin: keys.admin,
+            admin_token_account: keys.admin_token_account,
+            lp_pool: keys.lp_pool,
+            rewards_pool: keys.rewards_pool,
+            lp_fund: keys.lp_fund,
+            staking_pool: keys.staking_pool,
+            swap_wallet: keys.swap_wallet,
+            token_account: keys.token_account,
+            presale_escrow: keys.presale_escrow,
+            curve_reserve: keys.curve_reserve,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        instruction::InitializeToken {
+            total_supply,
+            whitelist_wallets: whitelist_wallets.clone(),
+            transfer_fee,
+            presale_tranche,
+        },
+    );
+    ix.accounts.extend(whitelist_wallets.iter().map(|wallet| {
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(wallet, &keys.mint, &keys.token_program),
+            false,
+        )
+    }));
+    ix
+}
+
+/// Accounts for [`transfer_tokens`]
+pub struct TransferTokensAccounts {
+    pub mint: Pubkey,
+    pub sender: Pubkey,
+    pub receiver: Pubkey,
+    /// Signing owner of `sender`, or its delegate for [`transfer_tokens_from`]
+    pub authority: Pubkey,
+    pub rewards_pool: Pubkey,
+    pub lp_fund: Pubkey,
+    pub lp_pool: Pubkey,
+    pub token_program: Pubkey,
+    /// `HolderRecord` of the receiver's owner, created on first receipt; needs `payer`
+    pub receiver_record: Option<Pubkey>,
+    /// Existing `HolderRecord` of the sender's owner, closed once the sender is emptied
+    pub sender_record: Option<Pubkey>,
+    /// `rent_payer` of `sender_record`
+    pub rent_recipient: Option<Pubkey>,
+    pub payer: Option<Pubkey>,
+    /// `ReferralRecord` of the sender's owner, required once referrals are
+    /// configured; set with [`Self::with_referral`]
+    pub sender_referral: Option<Pubkey>,
+    pub referrer_stats: Option<Pubkey>,
+    pub referral_vault: Option<Pubkey>,
+    /// [`pda::loyalty_tiers`]; the sender's discount needs `sender_record` as well
+    pub loyalty_tiers: Option<Pubkey>,
+    /// Required once a snapshot exists; set with [`Self::with_checkpoints`]
+    pub sender_checkpoints: Option<Pubkey>,
+    pub receiver_checkpoints: Option<Pubkey>,
+}
+
+impl TransferTokensAccounts {
+    /// Take the tax pools from a decoded `TokenState`
+    pub fn from_state(
+        mint: Pubkey,
+        sender: Pubkey,
+        receiver: Pubkey,
+        authority: Pubkey,
+        token_program: Pubkey,
+        state: &TokenState,
+    ) -> Self {
+        Self {
+            mint,
+            sender,
+            receiver,
+            authority,
+            rewards_pool: state.rewards_pool,
+            lp_fund: state.lp_fund,
+            lp_pool: state.lp_pool,
+            token_program,
+            receiver_record: None,
+            sender_record: None,
+            rent_recipient: None,
+            payer: None,
+            sender_referral: None,
+            referrer_stats: None,
+            referral_vault: None,
+            loyalty_tiers: None,
+            sender_checkpoints: None,
+            receiver_checkpoints: None,
+        }
+    }
+
+    /// Pass the referral record of `sender_owner`, crediting `referrer` if it
+    /// registered one
+    pub fn with_referral(
+        mut self,
+        sender_owner: &Pubkey,
+        referrer: Option<&Pubkey>,
+        state: &TokenState,
+    ) -> Self {
+        self.sender_referral = Some(pda::referral(&self.mint, sender_owner).0);
+        if let Some(referrer) = referrer {
+            self.referrer_stats = Some(pda::referrer_stats(&self.mint, referrer).0);
+            self.referral_vault = Some(state.referral_vault);
+        }
+        self
+    }
+
+    /// Checkpoint both balances for snapshots; new checkpoint accounts need `payer`
+    pub fn with_checkpoints(mut self) -> Self {
+        self.sender_checkpoints = Some(pda::checkpoints(&self.sender).0);
+        self.receiver_checkpoints = Some(pda::checkpoints(&self.receiver).0);
+        self
+    }
+}
+
+fn transfer_tokens_accounts(keys: &TransferTokensAccounts) -> accounts::TransferTokens {
+    accounts::TransferTokens {
+        token_state: pda::token_state(&keys.mint).0,
+        sender: keys.sender,
+        receiver: keys.receiver,
+        rewards_pool: keys.rewards_pool,
+        lp_fund: keys.lp_fund,
+        lp_pool: keys.lp_pool,
+        authority: keys.authority,
+        mint_authority: pda::mint_authority(&keys.mint).0,
+        mint: keys.mint,
+        whitelist: pda::whitelist(&keys.mint).0,
+        token_program: keys.token_program,
+        receiver_record: keys.receiver_record,
+        sender_record: keys.sender_record,
+        rent_recipient: keys.rent_recipient,
+        payer: keys.payer,
+        system_program: keys.payer.map(|_| system_program::ID),
+        sender_referral: keys.sender_referral,
+        referrer_stats: keys.referrer_stats,
+        referral_vault: keys.referral_vault,
+        loyalty_tiers: keys.loyalty_tiers,
+        sender_checkpoints: keys.sender_checkpoints,
+        receiver_checkpoints: keys.receiver_checkpoints,
+    }
+}
+
+/// `transfer_tokens`, signed by the sender's owner
+pub fn transfer_tokens(keys: &TransferTokensAccounts, amount: u64) -> Instruction {
+    build(
+        transfer_tokens_accounts(keys),
+        instruction::TransferTokens { amount },
+    )
+}
+
+/// `transfer_tokens_from`, signed by a delegate the owner approved for at least `amount`
+pub fn transfer_tokens_from(keys: &TransferTokensAccounts, amount: u64) -> Instruction {
+    build(
+        transfer_tokens_accounts(keys),
+        instruction::TransferTokensFrom { amount },
+    )
+}
+
+/// `batch_transfer` of `sender`, signed by its owner, who also pays for new
+/// holder records and checkpoint accounts; the sender's holder record and
+/// checkpoints, then each recipient with its own, are appended as remaining
+/// accounts in order. `rent_recipient` is the `rent_payer` of the sender's holder
+/// record, refunded if the batch empties the sender, and `referrer` the one the
+/// owner registered, if any
+pub fn batch_transfer(
+    mint: &Pubkey,
+    sender: &Pubkey,
+    authority: &Pubkey,
+    token_program: &Pubkey,
+    state: &TokenState,
+    rent_recipient: Option<Pubkey>,
+    referrer: Option<&Pubkey>,
+    transfers: Vec<BatchTransferItem>,
+) -> Instruction {
+    let referrals = state.referral_vault != Pubkey::default();
+    let mut ix = build(
+        accounts::BatchTransfer {
+            token_state: pda::token_state(mint).0,
+            sender: *sender,
+            rewards_pool: state.rewards_pool,
+            lp_fund: state.lp_fund,
+            lp_pool: state.lp_pool,
+            authority: *authority,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            whitelist: pda::whitelist(mint).0,
+            token_program: *token_program,
+            loyalty_tiers: (state.loyalty_min_balance > 0).then(|| pda::loyalty_tiers(mint).0),
+            payer: Some(*authority),
+            system_program: Some(system_program::ID),
+            rent_recipient,
+            sender_referral: referrals.then(|| pda::referral(mint, authority).0),
+            referrer_stats: referrer
+                .filter(|_| referrals)
+                .map(|referrer| pda::referrer_stats(mint, referrer).0),
+            referral_vault: referrer.filter(|_| referrals).map(|_| state.referral_vault),
+        },
+        instruction::BatchTransfer {
+            transfers: transfers.clone(),
+        },
+    );
+    let tracked = |token_account: &Pubkey| {
+        [
+            AccountMeta::new(pda::holder_record(mint, token_account).0, false),
+            AccountMeta::new(pda::checkpoints(token_account).0, false),
+        ]
+    };
+    ix.accounts.extend(tracked(sender));
+    for transfer in &transfers {
+        ix.accounts
+            .push(AccountMeta::new(transfer.recipient, false));
+        ix.accounts.extend(tracked(&transfer.recipient));
+    }
+    ix
+}
+
+/// `manual_burn`; `rent_recipient` is the holder record's `rent_payer`, refunded
+/// if the burn empties the account
+pub fn manual_burn(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    admin_token_account: &Pubkey,
+    token_program: &Pubkey,
+    rent_recipient: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::ManualBurn {
+            mint: *mint,
+            admin_token_account: *admin_token_account,
+            admin: *admin,
+            token_program: *token_program,
+            token_state: pda::token_state(mint).0,
+            checkpoints: Some(pda::checkpoints(admin_token_account).0),
+            system_program: Some(system_program::ID),
+            holder_record: Some(pda::holder_record(mint, admin_token_account).0),
+            rent_recipient,
+        },
+        instruction::ManualBurn { amount },
+    )
+}
+
+/// `user_burn`; `rent_recipient` is the holder record's `rent_payer`, refunded
+/// if the burn empties the account
+pub fn user_burn(
+    mint: &Pubkey,
+    user: &Pubkey,
+    user_token_account: &Pubkey,
+    token_program: &Pubkey,
+    rent_recipient: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::UserBurn {
+            token_state: pda::token_state(mint).0,
+            user_token_account: *user_token_account,
+            user: *user,
+            mint: *mint,
+            token_program: *token_program,
+            checkpoints: Some(pda::checkpoints(user_token_account).0),
+            payer: Some(*user),
+            system_program: Some(system_program::ID),
+            holder_record: Some(pda::holder_record(mint, user_token_account).0),
+            rent_recipient,
+        },
+        instruction::UserBurn { amount },
+    )
+}
+
+/// `prepare_rewards_swap`
+pub fn prepare_rewards_swap(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    rewards_pool: &Pubkey,
+    swap_wallet: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::SwapRewards {
+            rewards_pool: *rewards_pool,
+            swap_wallet: *swap_wallet,
+            admin: *admin,
+            token_program: *token_program,
+            token_state: pda::token_state(mint).0,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+        },
+        instruction::PrepareRewardsSwap { amount },
+    )
+}
+
+/// `transfer_ownership`
+pub fn transfer_ownership(mint: &Pubkey, admin: &Pubkey, new_admin: Pubkey) -> Instruction {
+    build(
+        accounts::TransferOwnership {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint: *mint,
+        },
+        instruction::TransferOwnership { new_admin },
+    )
+}
+
+/// `freeze_contract`
+pub fn freeze_contract(mint: &Pubkey, admin: &Pubkey, freeze: bool) -> Instruction {
+    build(
+        accounts::FreezeContract {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint: *mint,
+        },
+        instruction::FreezeContract { freeze },
+    )
+}
+
+/// `add_to_whitelist`
+pub fn add_to_whitelist(mint: &Pubkey, admin: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::WhitelistOperation {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            whitelist: pda::whitelist(mint).0,
+            mint: *mint,
+        },
+        instruction::AddToWhitelist { wallet },
+    )
+}
+
+/// `remove_from_whitelist`
+pub fn remove_from_whitelist(mint: &Pubkey, admin: &Pubkey, wallet: Pubkey) -> Instruction {
+    build(
+        accounts::WhitelistOperation {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            whitelist: pda::whitelist(mint).0,
+            mint: *mint,
+        },
+        instruction::RemoveFromWhitelist { wallet },
+    )
+}
+
+/// `update_tvl_data`
+pub fn update_tvl_data(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    lp_pool: &Pubkey,
+    rewards_pool: &Pubkey,
+    staking_pool: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::UpdateTVL {
+            token_state: pda::token_state(mint).0,
+            lp_pool: *lp_pool,
+            rewards_pool: *rewards_pool,
+            staking_pool: *staking_pool,
+            admin: *admin,
+            mint: *mint,
+        },
+        instruction::UpdateTvlData {},
+    )
+}
+
+/// `finalize_supply`
+pub fn finalize_supply(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    token_program: &Pubkey,
+    revoke_freeze_authority: bool,
+) -> Instruction {
+    build(
+        accounts::FinalizeSupply {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            token_program: *token_program,
+        },
+        instruction::FinalizeSupply {
+            revoke_freeze_authority,
+        },
+    )
+}
+
+/// `configure_emission`
+pub fn configure_emission(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    emission_recipient: &Pubkey,
+    max_supply: u64,
+    emission_per_epoch: u64,
+    epoch_duration: i64,
+) -> Instruction {
+    build(
+        accounts::ConfigureEmission {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint: *mint,
+            emission_recipient: *emission_recipient,
+        },
+        instruction::ConfigureEmission {
+            max_supply,
+            emission_per_epoch,
+            epoch_duration,
+        },
+    )
+}
+
+/// `configure_launch_phases`; phase starts are unix timestamps
+pub fn configure_launch_phases(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    whitelist_phase_start: i64,
+    limited_phase_start: i64,
+    public_phase_start: i64,
+    limited_max_transfer: u64,
+) -> Instruction {
+    build(
+        accounts::ConfigureLaunchPhases {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint: *mint,
+        },
+        instruction::ConfigureLaunchPhases {
+            whitelist_phase_start,
+            limited_phase_start,
+            public_phase_start,
+            limited_max_transfer,
+        },
+    )
+}
+
+/// `configure_launch_tax`; signed by the admin before trading opens
+pub fn configure_launch_tax(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    start_bps: u16,
+    window: i64,
+    steps: u8,
+) -> Instruction {
+    build(
+        accounts::ConfigureLaunchTax {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint: *mint,
+        },
+        instruction::ConfigureLaunchTax {
+            start_bps,
+            window,
+            steps,
+        },
+    )
+}
+
+/// `emit_epoch`; permissionless, no signer besides the fee payer
+pub fn emit_epoch(
+    mint: &Pubkey,
+    emission_recipient: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::EmitEpoch {
+            token_state: pda::token_state(mint).0,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            emission_recipient: *emission_recipient,
+            token_program: *token_program,
+        },
+        instruction::EmitEpoch {},
+    )
+}
+
+/// `initialize_transfer_hook`
+pub fn initialize_transfer_hook(mint: &Pubkey, admin: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeTransferHook {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            extra_account_meta_list: pda::extra_account_metas(mint).0,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeTransferHook {},
+    )
+}
+
+/// `initialize_confidential_mint`; `mint` is a fresh keypair that must sign
+pub fn initialize_confidential_mint(
+    payer: &Pubkey,
+    mint: &Pubkey,
+    decimals: u8,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::InitializeConfidentialMint {
+            payer: *payer,
+            mint: *mint,
+            mint_authority: pda::mint_authority(mint).0,
+            confidential_config: pda::confidential_config(mint).0,
+            token_program: anchor_spl::token_2022::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfidentialMint {
+            decimals,
+            auditor_elgamal_pubkey,
+        },
+    )
+}
+
+fn confidential_admin(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    token_account: Option<Pubkey>,
+) -> accounts::ConfidentialAdmin {
+    accounts::ConfidentialAdmin {
+        token_state: pda::token_state(mint).0,
+        admin: *admin,
+        whitelist: pda::whitelist(mint).0,
+        confidential_config: pda::confidential_config(mint).0,
+        mint_authority: pda::mint_authority(mint).0,
+        mint: *mint,
+        token_account,
+        token_program: anchor_spl::token_2022::ID,
+    }
+}
+
+/// `update_confidential_config`
+pub fn update_confidential_config(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+) -> Instruction {
+    build(
+        confidential_admin(mint, admin, None),
+        instruction::UpdateConfidentialConfig {
+            auditor_elgamal_pubkey,
+        },
+    )
+}
+
+/// `approve_confidential_account`; the account's owner must be whitelisted
+pub fn approve_confidential_account(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    token_account: &Pubkey,
+) -> Instruction {
+    build(
+        confidential_admin(mint, admin, Some(*token_account)),
+        instruction::ApproveConfidentialAccount {},
+    )
+}
+
+/// Accounts for [`confidential_deposit`] and [`confidential_withdraw`]
+pub struct ConfidentialBalanceAccounts {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub token_account: Pubkey,
+    /// `rent_payer` of the `HolderRecord`, refunded if a deposit empties the public balance
+    pub rent_recipient: Option<Pubkey>,
+}
+
+fn confidential_balance(
+    keys: &ConfidentialBalanceAccounts,
+    proof_context: Option<Pubkey>,
+) -> accounts::ConfidentialBalance {
+    accounts::ConfidentialBalance {
+        token_state: pda::token_state(&keys.mint).0,
+        confidential_config: pda::confidential_config(&keys.mint).0,
+        owner: keys.owner,
+        token_account: keys.token_account,
+        mint: keys.mint,
+        proof_context,
+        token_program: anchor_spl::token_2022::ID,
+        checkpoints: Some(pda::checkpoints(&keys.token_account).0),
+        payer: Some(keys.owner),
+        system_program: Some(system_program::ID),
+        holder_record: Some(pda::holder_record(&keys.mint, &keys.token_account).0),
+        rent_recipient: keys.rent_recipient,
+    }
+}
+
+/// `confidential_deposit`
+pub fn confidential_deposit(keys: &ConfidentialBalanceAccounts, amount: u64) -> Instruction {
+    build(
+        confidential_balance(keys, None),
+        instruction::ConfidentialDeposit { amount },
+    )
+}
+
+/// `confidential_withdraw`; `proof_context` holds a pre-verified withdraw proof
+pub fn confidential_withdraw(
+    keys: &ConfidentialBalanceAccounts,
+    proof_context: &Pubkey,
+    amount: u64,
+    new_decryptable_available_balance: [u8; 36],
+) -> Instruction {
+    build(
+        confidential_balance(keys, Some(*proof_context)),
+        instruction::ConfidentialWithdraw {
+            amount,
+            new_decryptable_available_balance,
+        },
+    )
+}
+
+/// `reconcile_supply`; permissionless, but only the admin may correct pool balances
+pub fn reconcile_supply(
+    mint: &Pubkey,
+    authority: &Pubkey,
+    lp_pool: &Pubkey,
+    rewards_pool: &Pubkey,
+    staking_pool: &Pubkey,
+    correct: bool,
+) -> Instruction {
+    build(
+        accounts::ReconcileSupply {
+            token_state: pda::token_state(mint).0,
+            mint: *mint,
+            lp_pool: *lp_pool,
+            rewards_pool: *rewards_pool,
+            staking_pool: *staking_pool,
+            authority: *authority,
+        },
+        instruction::ReconcileSupply { correct },
+    )
+}
+
+/// `register_holder`; records `token_account` as a holder
+pub fn register_holder(mint: &Pubkey, payer: &Pubkey, token_account: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterHolder {
+            token_state: pda::token_state(mint).0,
+            mint: *mint,
+            token_account: *token_account,
+            holder_record: pda::holder_record(mint, token_account).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterHolder {},
+    )
+}
+
+/// `set_pool_address`; `new_pool` must be a token account of `mint`
+pub fn set_pool_address(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    pool: PoolKind,
+    new_pool: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::SetPoolAddress {
+            token_state: pda::token_state(mint).0,
+            admin: *admin,
+            mint: *mint,
+            new_pool: *new_pool,
+        },
+        instruction::SetPoolAddress { pool },
+    )
+}
+
+/// Accounts for [`harvest_and_split_fees`]
+pub struct HarvestFeesAccounts {
+    pub mint: Pubkey,
+    pub fee_vault: Pubkey,
+    pub rewards_pool: Pubkey,
+    pub lp_fund: Pubkey,
+}
+
+impl HarvestFeesAccounts {
+    /// Take the tax pools from a decoded `TokenState`
+    pub fn from_state(mint: Pubkey, fee_vault: Pubkey, state: &TokenState) -> Self {
+        Self {
+            mint,
+            fee_vault,
+            rewards_pool: state.rewards_pool,
+            lp_fund: state.lp_fund,
+        }
+    }
+}
+
+/// `harvest_and_split_fees`; `sources` are token accounts holding withheld fees
+pub fn harvest_and_split_fees(keys: &HarvestFeesAccounts, sources: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::HarvestFees {
+            token_state: pda::token_state(&keys.mint).0,
+            mint_authority: pda::mint_authority(&keys.mint).0,
+            mint: keys.mint,
+            fee_vault: keys.fee_vault,
+            rewards_pool: keys.rewards_pool,
+            lp_fund: keys.lp_fund,
+            token_program: anchor_spl::token_2022::ID,
+        },
+        instruction::HarvestAndSplitFees {},
+    );
+    ix.accounts.extend(
+        sources
+            .iter()
+            .map(|source| AccountMeta::new(*source, false)),
+    );
+    ix
+}
+
+/// `configure_presale`; caps are in lamports, times are unix timestamps
+#[allow(clippy::too_many_arguments)]
+pub fn configure_presale(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    tokens_per_sol: u64,
+    soft_cap: u64,
+    hard_cap: u64,
+    wallet_cap: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Instruction {
+    build(
+        accounts::ConfigurePresale {
+            token_state: pda::token_state(mint).0,
+            presale: pda::presale(mint).0,
+            admin: *admin,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigurePresale {
+            tokens_per_sol,
+            soft_cap,
+            hard_cap,
+            wallet_cap,
+            start_time,
+            end_time,
+        },
+    )
+}
+
+/// `contribute_presale`; `amount` lamports are paid by the contributor
+pub fn contribute_presale(mint: &Pubkey, contributor: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::ContributePresale {
+            presale: pda::presale(mint).0,
+            receipt: pda::presale_receipt(mint, contributor).0,
+            contributor: *contributor,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::ContributePresale { amount },
+    )
+}
+
+/// `claim_presale`; signed by the contributor once the sale has succeeded
+pub fn claim_presale(
+    mint: &Pubkey,
+    contributor: &Pubkey,
+    contributor_token_account: &Pubkey,
+    presale_escrow: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ClaimPresale {
+            token_state: pda::token_state(mint).0,
+            presale: pda::presale(mint).0,
+            receipt: pda::presale_receipt(mint, contributor).0,
+            contributor: *contributor,
+            contributor_token_account: *contributor_token_account,
+            presale_escrow: *presale_escrow,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            token_program: *token_program,
+            checkpoints: Some(pda::checkpoints(contributor_token_account).0),
+            system_program: Some(system_program::ID),
+        },
+        instruction::ClaimPresale {},
+    )
+}
+
+/// `refund_presale`; signed by the contributor once the sale has failed
+pub fn refund_presale(mint: &Pubkey, contributor: &Pubkey) -> Instruction {
+    build(
+        accounts::RefundPresale {
+            presale: pda::presale(mint).0,
+            receipt: pda::presale_receipt(mint, contributor).0,
+            contributor: *contributor,
+            mint: *mint,
+        },
+        instruction::RefundPresale {},
+    )
+}
+
+/// `finalize_presale`; pays the proceeds to the admin and returns unsold tokens
+pub fn finalize_presale(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    admin_token_account: &Pubkey,
+    presale_escrow: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::FinalizePresale {
+            token_state: pda::token_state(mint).0,
+            presale: pda::presale(mint).0,
+            admin: *admin,
+            admin_token_account: *admin_token_account,
+            presale_escrow: *presale_escrow,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            token_program: *token_program,
+            checkpoints: Some(pda::checkpoints(admin_token_account).0),
+            system_program: Some(system_program::ID),
+        },
+        instruction::FinalizePresale {},
+    )
+}
+
+/// `configure_bonding_curve`; the curve sells whatever `curve_reserve` holds
+pub fn configure_bonding_curve(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    curve_reserve: &Pubkey,
+    shape: CurveShape,
+    migration_market_cap: u64,
+) -> Instruction {
+    build(
+        accounts::ConfigureBondingCurve {
+            token_state: pda::token_state(mint).0,
+            bonding_curve: pda::bonding_curve(mint).0,
+            curve_reserve: *curve_reserve,
+            admin: *admin,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigureBondingCurve {
+            shape,
+            migration_market_cap,
+        },
+    )
+}
+
+/// Accounts for [`buy_on_curve`] and [`sell_on_curve`]
+pub struct CurveTradeAccounts {
+    pub mint: Pubkey,
+    pub trader: Pubkey,
+    pub trader_token_account: Pubkey,
+    pub curve_reserve: Pubkey,
+    pub lp_pool: Pubkey,
+    pub rewards_pool: Pubkey,
+    pub lp_fund: Pubkey,
+    /// `TokenState::admin`, paid the SOL reserve on migration
+    pub migration_payout: Pubkey,
+    pub token_program: Pubkey,
+    /// `rent_payer` of the trader's `HolderRecord`, refunded if a sell empties the account
+    pub rent_recipient: Option<Pubkey>,
+}
+
+impl CurveTradeAccounts {
+    /// Take the reserve, pools and admin from a decoded `TokenState`
+    pub fn from_state(
+        mint: Pubkey,
+        trader: Pubkey,
+        trader_token_account: Pubkey,
+        token_program: Pubkey,
+        state: &TokenState,
+    ) -> Self {
+        Self {
+            mint,
+            trader,
+            trader_token_account,
+            curve_reserve: state.curve_reserve,
+            lp_pool: state.lp_pool,
+            rewards_pool: state.rewards_pool,
+            lp_fund: state.lp_fund,
+            migration_payout: state.admin,
+            token_program,
+            rent_recipient: None,
+        }
+    }
+
+    fn accounts(&self) -> accounts::CurveTrade {
+        accounts::CurveTrade {
+            token_state: pda::token_state(&self.mint).0,
+            whitelist: pda::whitelist(&self.mint).0,
+            bonding_curve: pda::bonding_curve(&self.mint).0,
+            curve_reserve: self.curve_reserve,
+            trader: self.trader,
+            trader_token_account: self.trader_token_account,
+            lp_pool: self.lp_pool,
+            rewards_pool: self.rewards_pool,
+            lp_fund: self.lp_fund,
+            migration_payout: self.migration_payout,
+            mint_authority: pda::mint_authority(&self.mint).0,
+            mint: self.mint,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+            trader_checkpoints: Some(pda::checkpoints(&self.trader_token_account).0),
+            trader_record: Some(pda::holder_record(&self.mint, &self.trader_token_account).0),
+            rent_recipient: self.rent_recipient,
+        }
+    }
+}
+
+/// `buy_on_curve`; pays at most `max_lamports` for `amount` tokens before tax
+pub fn buy_on_curve(keys: &CurveTradeAccounts, amount: u64, max_lamports: u64) -> Instruction {
+    build(
+        keys.accounts(),
+        instruction::BuyOnCurve {
+            amount,
+            max_lamports,
+        },
+    )
+}
+
+/// `sell_on_curve`; receives at least `min_lamports` for `amount` tokens before tax
+pub fn sell_on_curve(keys: &CurveTradeAccounts, amount: u64, min_lamports: u64) -> Instruction {
+    build(
+        keys.accounts(),
+        instruction::SellOnCurve {
+            amount,
+            min_lamports,
+        },
+    )
+}
+
+/// `configure_referrals`; `referral_vault` must be owned by [`pda::mint_authority`]
+pub fn configure_referrals(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    referral_vault: &Pubkey,
+    referral_bps: u16,
+) -> Instruction {
+    build(
+        accounts::ConfigureReferrals {
+            token_state: pda::token_state(mint).0,
+            referral_vault: *referral_vault,
+            mint_authority: pda::mint_authority(mint).0,
+            admin: *admin,
+            mint: *mint,
+        },
+        instruction::ConfigureReferrals { referral_bps },
+    )
+}
+
+/// `register_referrer`; signed and paid for by `user`
+pub fn register_referrer(mint: &Pubkey, user: &Pubkey, referrer: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterReferrer {
+            referral: pda::referral(mint, user).0,
+            referrer: *referrer,
+            referrer_stats: pda::referrer_stats(mint, referrer).0,
+            user: *user,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterReferrer {},
+    )
+}
+
+/// `claim_referral_rewards`; pays the referrer's whole claimable balance
+pub fn claim_referral_rewards(
+    mint: &Pubkey,
+    referrer: &Pubkey,
+    referrer_token_account: &Pubkey,
+    referral_vault: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ClaimReferralRewards {
+            token_state: pda::token_state(mint).0,
+            referrer_stats: pda::referrer_stats(mint, referrer).0,
+            referrer: *referrer,
+            referrer_token_account: *referrer_token_account,
+            referral_vault: *referral_vault,
+            mint_authority: pda::mint_authority(mint).0,
+            mint: *mint,
+            token_program: *token_program,
+            checkpoints: Some(pda::checkpoints(referrer_token_account).0),
+            payer: Some(*referrer),
+            system_program: Some(system_program::ID),
+        },
+        instruction::ClaimReferralRewards {},
+    )
+}
+
+/// `configure_loyalty_tiers`; an empty list turns the discounts off
+pub fn configure_loyalty_tiers(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    tiers: Vec<LoyaltyTier>,
+) -> Instruction {
+    build(
+        accounts::ConfigureLoyaltyTiers {
+            token_state: pda::token_state(mint).0,
+            loyalty_tiers: pda::loyalty_tiers(mint).0,
+            admin: *admin,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigureLoyaltyTiers { tiers },
+    )
+}
+
+/// `configure_governance`
+pub fn configure_governance(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    voting_period: i64,
+    quorum: u64,
+    proposal_threshold: u64,
+) -> Instruction {
+    build(
+        accounts::ConfigureGovernance {
+            token_state: pda::token_state(mint).0,
+            governance: pda::governance(mint).0,
+            admin: *admin,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::ConfigureGovernance {
+            voting_period,
+            quorum,
+            proposal_threshold,
+        },
+    )
+}
+
+/// `create_proposal`; `id` is the governance's current `proposal_count` and
+/// `snapshot_id` the token state's `snapshot_id` plus one
+pub fn create_proposal(
+    mint: &Pubkey,
+    proposer: &Pubkey,
+    proposer_token_account: &Pubkey,
+    id: u64,
+    snapshot_id: u64,
+    action: ProposalAction,
+) -> Instruction {
+    build(
+        accounts::CreateProposal {
+            token_state: pda::token_state(mint).0,
+            governance: pda::governance(mint).0,
+            proposal: pda::proposal(mint, id).0,
+            snapshot: pda::snapshot(mint, snapshot_id).0,
+            proposer_token_account: *proposer_token_account,
+            proposer: *proposer,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal { action },
+    )
+}
+
+/// `cast_vote` with the balance `voter_token_account` held at the proposal's snapshot
+pub fn cast_vote(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    voter_token_account: &Pubkey,
+    id: u64,
+    approve: bool,
+) -> Instruction {
+    let proposal = pda::proposal(mint, id).0;
+    build(
+        accounts::CastVote {
+            token_state: pda::token_state(mint).0,
+            proposal,
+            voter_token_account: *voter_token_account,
+            checkpoints: pda::checkpoints(voter_token_account).0,
+            vote_record: pda::vote_record(&proposal, voter_token_account).0,
+            owner: *owner,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::CastVote { approve },
+    )
+}
+
+/// `execute_proposal`, appending the accounts `action` needs beyond the token state
+pub fn execute_proposal(
+    mint: &Pubkey,
+    id: u64,
+    action: &ProposalAction,
+    state: &TokenState,
+) -> Instruction {
+    let mut ix = build(
+        accounts::ExecuteProposal {
+            governance: pda::governance(mint).0,
+            proposal: pda::proposal(mint, id).0,
+            token_state: pda::token_state(mint).0,
+            mint: *mint,
+            this_program: ID,
+        },
+        instruction::ExecuteProposal {},
+    );
+    match action {
+        ProposalAction::AddToWhitelist { .. } | ProposalAction::RemoveFromWhitelist { .. } => {
+            ix.accounts
+                .push(AccountMeta::new(pda::whitelist(mint).0, false));
+        }
+        ProposalAction::ConfigureReferrals { .. } => {
+            ix.accounts
+                .push(AccountMeta::new_readonly(state.referral_vault, false));
+            ix.accounts.push(AccountMeta::new_readonly(
+                pda::mint_authority(mint).0,
+                false,
+            ));
+        }
+        ProposalAction::ConfigureLaunchTax { .. } | ProposalAction::FreezeContract { .. } => {}
+    }
+    ix
+}
+
+/// `take_snapshot`; `id` is the token state's `snapshot_id` plus one
+pub fn take_snapshot(mint: &Pubkey, admin: &Pubkey, id: u64) -> Instruction {
+    build(
+        accounts::TakeSnapshot {
+            token_state: pda::token_state(mint).0,
+            snapshot: pda::snapshot(mint, id).0,
+            admin: *admin,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::TakeSnapshot {},
+    )
+}
+
+/// `register_checkpoints` for `token_account`, paid by `payer`; voting on a
+/// proposal needs them opened before it
+pub fn register_checkpoints(mint: &Pubkey, payer: &Pubkey, token_account: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterCheckpoints {
+            token_state: pda::token_state(mint).0,
+            token_account: *token_account,
+            checkpoints: pda::checkpoints(token_account).0,
+            payer: *payer,
+            mint: *mint,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterCheckpoints {},
+    )
+}
+
+/// `balance_at`; the balance is returned as little-endian `u64` return data
+pub fn balance_at(mint: &Pubkey, token_account: &Pubkey, snapshot_id: u64) -> Instruction {
+    build(
+        accounts::BalanceAt {
+            token_state: pda::token_state(mint).0,
+            token_account: *token_account,
+            checkpoints: pda::checkpoints(token_account).0,
+            mint: *mint,
+        },
+        instruction::BalanceAt { snapshot_id },
+    )
+}
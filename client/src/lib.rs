@@ -0,0 +1,11 @@
+//! Off-chain client SDK for the `my_solami_token` program.
+//!
+//! - [`pda`] derives the program addresses seeded by the mint
+//! - [`instructions`] builds typed instructions for every program instruction
+//! - [`accounts`] decodes every program account, checking its discriminator
+
+pub mod accounts;
+pub mod instructions;
+pub mod pda;
+
+pub use my_solami_token::ID as PROGRAM_ID;
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::Pubkey;
+use my_solami_token::ID;
+
+pub const TOKEN_STATE_SEED: &[u8] = b"token_state";
+pub const WHITELIST_SEED: &[u8] = b"whitelist";
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+pub const CONFIDENTIAL_CONFIG_SEED: &[u8] = b"confidential_config";
+pub const HOLDER_SEED: &[u8] = b"holder";
+pub const PRESALE_SEED: &[u8] = b"presale";
+pub const PRESALE_RECEIPT_SEED: &[u8] = b"presale_receipt";
+pub const BONDING_CURVE_SEED: &[u8] = b"bonding_curve";
+pub const REFERRAL_SEED: &[u8] = b"referral";
+pub const REFERRER_SEED: &[u8] = b"referrer";
+pub const LOYALTY_TIERS_SEED: &[u8] = b"loyalty_tiers";
+pub const GOVERNANCE_SEED: &[u8] = b"governance";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_SEED: &[u8] = b"vote";
+pub const SNAPSHOT_SEED: &[u8] = b"snapshot";
+pub const CHECKPOINTS_SEED: &[u8] = b"checkpoints";
+
+/// `TokenState` PDA for a mint
+pub fn token_state(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TOKEN_STATE_SEED, mint.as_ref()], &ID)
+}
+
+/// `Whitelist` PDA for a mint
+pub fn whitelist(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WHITELIST_SEED, mint.as_ref()], &ID)
+}
+
+/// PDA holding the mint, freeze, transfer hook and fee authorities
+pub fn mint_authority(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED, mint.as_ref()], &ID)
+}
+
+/// `HolderRecord` PDA for a token account holding the mint
+pub fn holder_record(mint: &Pubkey, token_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[HOLDER_SEED, mint.as_ref(), token_account.as_ref()], &ID)
+}
+
+/// `Presale` PDA for a mint, also the SOL vault and the escrow's owner
+pub fn presale(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PRESALE_SEED, mint.as_ref()], &ID)
+}
+
+/// `PresaleReceipt` PDA for a contributor
+pub fn presale_receipt(mint: &Pubkey, contributor: &Pubkey) -> (Pubkey, u8) {
+    let presale = presale(mint).0;
+    Pubkey::find_program_address(
+        &[PRESALE_RECEIPT_SEED, presale.as_ref(), contributor.as_ref()],
+        &ID,
+    )
+}
+
+/// `BondingCurve` PDA for a mint, also the SOL reserve and the token reserve's owner
+pub fn bonding_curve(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BONDING_CURVE_SEED, mint.as_ref()], &ID)
+}
+
+/// `ReferralRecord` PDA holding the referrer `user` registered
+pub fn referral(mint: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REFERRAL_SEED, mint.as_ref(), user.as_ref()], &ID)
+}
+
+/// `ReferrerStats` PDA with a referrer's stats and claimable rewards
+pub fn referrer_stats(mint: &Pubkey, referrer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REFERRER_SEED, mint.as_ref(), referrer.as_ref()], &ID)
+}
+
+/// `LoyaltyTiers` PDA with the hold-duration tax discounts for a mint
+pub fn loyalty_tiers(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LOYALTY_TIERS_SEED, mint.as_ref()], &ID)
+}
+
+/// `Governance` PDA for a mint, also the admin it can be handed
+pub fn governance(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_SEED, mint.as_ref()], &ID)
+}
+
+/// `Proposal` PDA for the `id`th proposal of a mint
+pub fn proposal(mint: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_SEED, mint.as_ref(), &id.to_le_bytes()], &ID)
+}
+
+/// `VoteRecord` PDA for the ballot a token account cast on a proposal
+pub fn vote_record(proposal: &Pubkey, token_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VOTE_SEED, proposal.as_ref(), token_account.as_ref()], &ID)
+}
+
+/// `Snapshot` PDA for the `id`th snapshot of a mint, counting from 1
+pub fn snapshot(mint: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SNAPSHOT_SEED, mint.as_ref(), &id.to_le_bytes()], &ID)
+}
+
+/// `BalanceCheckpoints` PDA of a token account
+pub fn checkpoints(token_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CHECKPOINTS_SEED, token_account.as_ref()], &ID)
+}
+
+/// `ConfidentialConfig` PDA for a confidential mint
+pub fn confidential_config(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIDENTIAL_CONFIG_SEED, mint.as_ref()], &ID)
+}
+
+/// Transfer hook validation account holding the extra account metas
+pub fn extra_account_metas(mint: &Pubkey) -> (Pubkey, u8) {
+    spl_transfer_hook_interface::get_extra_account_metas_address_and_bump_seed(mint, &ID)
+}
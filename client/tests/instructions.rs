@@ -0,0 +1,210 @@
+//! Round-trip checks of the instruction builders against the program's
+//! account order and Anchor's instruction encoding.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::{hash::hash, instruction::Instruction, system_program, sysvar};
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use my_solami_token::{instruction, state::TransferFeeParams};
+use my_solami_token_client::{instructions, pda, PROGRAM_ID};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+/// Anchor's sighash: the first 8 bytes of `sha256("global:<name>")`
+fn sighash(name: &str) -> [u8; 8] {
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Split `ix` data into its discriminator and decoded arguments
+fn decode<T: AnchorDeserialize>(ix: &Instruction) -> ([u8; 8], T) {
+    assert_eq!(ix.program_id, PROGRAM_ID);
+    let (discriminator, mut data) = ix.data.split_at(8);
+    let args = T::deserialize(&mut data).unwrap();
+    assert!(data.is_empty(), "trailing instruction data");
+    (discriminator.try_into().unwrap(), args)
+}
+
+/// Keys of `ix` with their (signer, writable) flags
+fn metas(ix: &Instruction) -> Vec<(Pubkey, bool, bool)> {
+    ix.accounts
+        .iter()
+        .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+        .collect()
+}
+
+fn transfer_keys() -> instructions::TransferTokensAccounts {
+    instructions::TransferTokensAccounts {
+        mint: Pubkey::new_unique(),
+        sender: Pubkey::new_unique(),
+        receiver: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        rewards_pool: Pubkey::new_unique(),
+        lp_fund: Pubkey::new_unique(),
+        lp_pool: Pubkey::new_unique(),
+        token_program: anchor_spl::token::ID,
+        receiver_record: None,
+        sender_record: None,
+        rent_recipient: None,
+        payer: None,
+        sender_referral: None,
+        referrer_stats: None,
+        referral_vault: None,
+        loyalty_tiers: None,
+        sender_checkpoints: None,
+        receiver_checkpoints: None,
+    }
+}
+
+#[test]
+fn transfer_tokens_follows_the_program_account_order() {
+    let keys = transfer_keys().with_checkpoints();
+    let ix = instructions::transfer_tokens(&keys, 42);
+
+    let (discriminator, args) = decode::<instruction::TransferTokens>(&ix);
+    assert_eq!(discriminator, sighash("transfer_tokens"));
+    assert_eq!(discriminator, instruction::TransferTokens::DISCRIMINATOR);
+    assert_eq!(args.amount, 42);
+
+    let mint = keys.mint;
+    // Unset optional accounts are passed as the program id
+    let none = (PROGRAM_ID, false, false);
+    assert_eq!(
+        metas(&ix),
+        vec![
+            (pda::token_state(&mint).0, false, true),
+            (keys.sender, false, true),
+            (keys.receiver, false, true),
+            (keys.rewards_pool, false, true),
+            (keys.lp_fund, false, true),
+            (keys.lp_pool, false, true),
+            (keys.authority, true, false),
+            (pda::mint_authority(&mint).0, false, false),
+            (mint, false, true),
+            (pda::whitelist(&mint).0, false, false),
+            (keys.token_program, false, false),
+            none,
+            none,
+            none,
+            none,
+            none,
+            none,
+            none,
+            none,
+            none,
+            (pda::checkpoints(&keys.sender).0, false, true),
+            (pda::checkpoints(&keys.receiver).0, false, true),
+        ]
+    );
+}
+
+#[test]
+fn transfer_tokens_from_shares_accounts_but_not_the_discriminator() {
+    let keys = transfer_keys();
+    let owner_signed = instructions::transfer_tokens(&keys, 7);
+    let delegated = instructions::transfer_tokens_from(&keys, 7);
+
+    let (discriminator, args) = decode::<instruction::TransferTokensFrom>(&delegated);
+    assert_eq!(discriminator, sighash("transfer_tokens_from"));
+    assert_ne!(delegated.data[..8], owner_signed.data[..8]);
+    assert_eq!(args.amount, 7);
+    assert_eq!(metas(&delegated), metas(&owner_signed));
+}
+
+#[test]
+fn initialize_token_appends_whitelisted_atas_of_its_token_program() {
+    let keys = instructions::InitializeTokenAccounts {
+        mint: Pubkey::new_unique(),
+        admin: Pubkey::new_unique(),
+        admin_token_account: Pubkey::new_unique(),
+        lp_pool: Pubkey::new_unique(),
+        rewards_pool: Pubkey::new_unique(),
+        lp_fund: Pubkey::new_unique(),
+        staking_pool: Pubkey::new_unique(),
+        swap_wallet: Pubkey::new_unique(),
+        token_account: Pubkey::new_unique(),
+        presale_escrow: None,
+        curve_reserve: None,
+        token_program: anchor_spl::token_2022::ID,
+    };
+    let wallets = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let fee = TransferFeeParams {
+        basis_points: 1_000,
+        maximum_fee: 5_000,
+    };
+    let ix = instructions::initialize_token(&keys, 1_000_000, wallets.clone(), Some(fee), 0);
+
+    let (discriminator, args) = decode::<instruction::InitializeToken>(&ix);
+    assert_eq!(discriminator, sighash("initialize_token"));
+    assert_eq!(args.total_supply, 1_000_000);
+    assert_eq!(args.whitelist_wallets, wallets);
+    let fee = args.transfer_fee.unwrap();
+    assert_eq!((fee.basis_points, fee.maximum_fee), (1_000, 5_000));
+    assert_eq!(args.presale_tranche, 0);
+
+    let accounts = metas(&ix);
+    let (fixed, remaining) = accounts.split_at(accounts.len() - wallets.len());
+    assert_eq!(fixed[0], (pda::token_state(&keys.mint).0, false, true));
+    assert_eq!(fixed[fixed.len() - 2], (system_program::ID, false, false));
+    assert_eq!(fixed[fixed.len() - 1], (sysvar::rent::ID, false, false));
+    let atas: Vec<_> = wallets
+        .iter()
+        .map(|wallet| {
+            let ata = get_associated_token_address_with_program_id(
+                wallet,
+                &keys.mint,
+                &keys.token_program,
+            );
+            (ata, false, true)
+        })
+        .collect();
+    assert_eq!(remaining, atas);
+}
+
+#[test]
+fn harvest_and_split_fees_appends_writable_sources() {
+    let keys = instructions::HarvestFeesAccounts {
+        mint: Pubkey::new_unique(),
+        fee_vault: Pubkey::new_unique(),
+        rewards_pool: Pubkey::new_unique(),
+        lp_fund: Pubkey::new_unique(),
+    };
+    let sources = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let ix = instructions::harvest_and_split_fees(&keys, &sources);
+
+    let (discriminator, _) = decode::<instruction::HarvestAndSplitFees>(&ix);
+    assert_eq!(discriminator, sighash("harvest_and_split_fees"));
+    assert_eq!(
+        metas(&ix),
+        vec![
+            (pda::token_state(&keys.mint).0, false, true),
+            (pda::mint_authority(&keys.mint).0, false, false),
+            (keys.mint, false, true),
+            (keys.fee_vault, false, true),
+            (keys.rewards_pool, false, true),
+            (keys.lp_fund, false, true),
+            (anchor_spl::token_2022::ID, false, false),
+            (sources[0], false, true),
+            (sources[1], false, true),
+        ]
+    );
+}
+
+#[test]
+fn balance_at_reads_the_checkpoints_pda_of_the_token_account() {
+    let mint = Pubkey::new_unique();
+    let token_account = Pubkey::new_unique();
+    let ix = instructions::balance_at(&mint, &token_account, 3);
+
+    let (discriminator, args) = decode::<instruction::BalanceAt>(&ix);
+    assert_eq!(discriminator, sighash("balance_at"));
+    assert_eq!(args.snapshot_id, 3);
+    assert_eq!(
+        metas(&ix),
+        vec![
+            (pda::token_state(&mint).0, false, false),
+            (token_account, false, false),
+            (pda::checkpoints(&token_account).0, false, false),
+            (mint, false, false),
+        ]
+    );
+}
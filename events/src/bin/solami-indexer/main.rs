@@ -0,0 +1,153 @@
+//! `solami-indexer`: decodes `my_solami_token` events from transaction logs
+//! and appends them to a SQLite database with per-day aggregate views.
+
+mod store;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
+use my_solami_token_events::{parse_logs, PROGRAM_ID};
+use serde_json::Value;
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use std::str::FromStr;
+use store::{EventSource, Store};
+
+#[derive(Parser)]
+#[command(
+    name = "solami-indexer",
+    version,
+    about = "Index my_solami_token events into SQLite"
+)]
+struct Cli {
+    /// SQLite database to append to
+    #[arg(long, default_value = "solami-events.db")]
+    db: String,
+    /// Read `getTransaction` results from a JSON file instead of RPC
+    #[arg(long)]
+    json: Option<String>,
+    /// RPC endpoint, defaults to a local validator
+    #[arg(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Maximum number of recent program transactions to fetch over RPC
+    #[arg(long, default_value_t = 1000)]
+    limit: usize,
+    /// Program id to index, defaults to the deployed `my_solami_token` id
+    #[arg(long, default_value_t = PROGRAM_ID)]
+    program_id: Pubkey,
+}
+
+/// Logs of one confirmed transaction
+struct TransactionLogs {
+    signature: String,
+    slot: u64,
+    logs: Vec<String>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let store = Store::open(&cli.db)?;
+
+    let transactions = match &cli.json {
+        Some(path) => read_json(path)?,
+        None => fetch_rpc(&cli.url, &cli.program_id, cli.limit)?,
+    };
+
+    let (mut seen, mut added) = (0usize, 0usize);
+    for tx in &transactions {
+        for (event_index, event) in parse_logs(&cli.program_id, &tx.logs).iter().enumerate() {
+            let source = EventSource {
+                signature: &tx.signature,
+                slot: tx.slot,
+                event_index,
+            };
+            seen += 1;
+            if store.insert(&source, event)? {
+                added += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} transactions, {seen} events, {added} new rows in {}",
+        transactions.len(),
+        cli.db
+    );
+    Ok(())
+}
+
+/// Load a single `getTransaction` result or an array of them
+fn read_json(path: &str) -> Result<Vec<TransactionLogs>> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("reading {path}"))?;
+    let value: Value = serde_json::from_str(&raw)?;
+    let entries = match value {
+        Value::Array(entries) => entries,
+        entry => vec![entry],
+    };
+    entries.iter().map(transaction_from_json).collect()
+}
+
+fn transaction_from_json(entry: &Value) -> Result<TransactionLogs> {
+    // Accept both the bare result and the full JSON-RPC response
+    let entry = entry.get("result").unwrap_or(entry);
+    let signature = entry["transaction"]["signatures"][0]
+        .as_str()
+        .ok_or_else(|| anyhow!("transaction without signature"))?
+        .to_string();
+    let slot = entry["slot"].as_u64().unwrap_or_default();
+    let logs = entry["meta"]["logMessages"]
+        .as_array()
+        .map(|logs| {
+            logs.iter()
+                .filter_map(|line| line.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(TransactionLogs {
+        signature,
+        slot,
+        logs,
+    })
+}
+
+/// Fetch the most recent successful program transactions, oldest first
+fn fetch_rpc(url: &str, program_id: &Pubkey, limit: usize) -> Result<Vec<TransactionLogs>> {
+    let rpc = RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed());
+    let signatures = rpc.get_signatures_for_address_with_config(
+        program_id,
+        GetConfirmedSignaturesForAddress2Config {
+            limit: Some(limit),
+            ..Default::default()
+        },
+    )?;
+
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+
+    let mut transactions = Vec::with_capacity(signatures.len());
+    for status in signatures
+        .iter()
+        .rev()
+        .filter(|status| status.err.is_none())
+    {
+        let tx =
+            rpc.get_transaction_with_config(&Signature::from_str(&status.signature)?, config)?;
+        let logs = tx
+            .transaction
+            .meta
+            .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+            .unwrap_or_default();
+        transactions.push(TransactionLogs {
+            signature: status.signature.clone(),
+            slot: tx.slot,
+            logs,
+        });
+    }
+    Ok(transactions)
+}
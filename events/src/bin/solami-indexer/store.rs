@@ -0,0 +1,269 @@
+use anyhow::Result;
+use my_solami_token_events::ProgramEvent;
+use rusqlite::{params, Connection};
+
+/// Append-only event tables keyed by (signature, event_index), plus per-day views
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transfers (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    sender      TEXT    NOT NULL,
+    receiver    TEXT    NOT NULL,
+    amount      INTEGER NOT NULL,
+    tax_amount  INTEGER NOT NULL,
+    tax_rate_bps INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS burns (
+    signature        TEXT    NOT NULL,
+    event_index      INTEGER NOT NULL,
+    slot             INTEGER NOT NULL,
+    timestamp        INTEGER NOT NULL,
+    burner           TEXT    NOT NULL,
+    amount           INTEGER NOT NULL,
+    new_total_supply INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS swaps (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    pool        TEXT    NOT NULL,
+    amount      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS whitelist_changes (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    wallet      TEXT    NOT NULL,
+    is_added    INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS holder_changes (
+    signature    TEXT    NOT NULL,
+    event_index  INTEGER NOT NULL,
+    slot         INTEGER NOT NULL,
+    timestamp    INTEGER NOT NULL,
+    owner        TEXT    NOT NULL,
+    is_added     INTEGER NOT NULL,
+    holder_count INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS presale_contributions (
+    signature         TEXT    NOT NULL,
+    event_index       INTEGER NOT NULL,
+    slot              INTEGER NOT NULL,
+    timestamp         INTEGER NOT NULL,
+    contributor       TEXT    NOT NULL,
+    amount            INTEGER NOT NULL,
+    total_contributed INTEGER NOT NULL,
+    total_raised      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS curve_trades (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    trader      TEXT    NOT NULL,
+    is_buy      INTEGER NOT NULL,
+    amount      INTEGER NOT NULL,
+    tax_amount  INTEGER NOT NULL,
+    lamports    INTEGER NOT NULL,
+    tokens_sold INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS referral_rewards (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    referrer    TEXT    NOT NULL,
+    user        TEXT    NOT NULL,
+    tax_amount  INTEGER NOT NULL,
+    amount      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE TABLE IF NOT EXISTS fee_harvests (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    timestamp   INTEGER NOT NULL,
+    amount      INTEGER NOT NULL,
+    rewards     INTEGER NOT NULL,
+    lp_fund     INTEGER NOT NULL,
+    burned      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE VIEW IF NOT EXISTS daily_transfers AS
+    SELECT date(timestamp, 'unixepoch') AS day,
+           COUNT(*)                     AS transfers,
+           SUM(amount)                  AS volume,
+           SUM(tax_amount)              AS tax,
+           SUM(tax_amount > 0)          AS taxed_transfers
+    FROM transfers GROUP BY day;
+CREATE VIEW IF NOT EXISTS daily_burns AS
+    SELECT date(timestamp, 'unixepoch') AS day,
+           COUNT(*)                     AS burns,
+           SUM(amount)                  AS burned,
+           MIN(new_total_supply)        AS closing_supply
+    FROM burns GROUP BY day;
+CREATE VIEW IF NOT EXISTS daily_fee_harvests AS
+    SELECT date(timestamp, 'unixepoch') AS day,
+           SUM(amount)                  AS harvested,
+           SUM(rewards)                 AS rewards,
+           SUM(lp_fund)                 AS lp_fund,
+           SUM(burned)                  AS burned
+    FROM fee_harvests GROUP BY day;
+";
+
+pub struct Store {
+    conn: Connection,
+}
+
+/// Where an event sits on chain
+pub struct EventSource<'a> {
+    pub signature: &'a str,
+    pub slot: u64,
+    pub event_index: usize,
+}
+
+impl Store {
+    pub fn open(path: &str) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Insert an event, ignoring it if it was already indexed. Returns whether a row was added
+    pub fn insert(&self, source: &EventSource, event: &ProgramEvent) -> Result<bool> {
+        let (sig, slot, idx) = (
+            source.signature,
+            source.slot as i64,
+            source.event_index as i64,
+        );
+        let rows = match event {
+            ProgramEvent::Transfer(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO transfers VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.sender.to_string(),
+                    e.receiver.to_string(),
+                    e.amount as i64,
+                    e.tax_amount as i64,
+                    e.tax_rate_bps
+                ],
+            )?,
+            ProgramEvent::Burn(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO burns VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.burner.to_string(),
+                    e.amount as i64,
+                    e.new_total_supply as i64
+                ],
+            )?,
+            ProgramEvent::Swap(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO swaps VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.pool.to_string(),
+                    e.amount as i64
+                ],
+            )?,
+            ProgramEvent::Whitelist(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO whitelist_changes VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.wallet.to_string(),
+                    e.is_added
+                ],
+            )?,
+            ProgramEvent::Holder(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO holder_changes VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.owner.to_string(),
+                    e.is_added,
+                    e.holder_count as i64
+                ],
+            )?,
+            ProgramEvent::PresaleContribution(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO presale_contributions VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.contributor.to_string(),
+                    e.amount as i64,
+                    e.total_contributed as i64,
+                    e.total_raised as i64
+                ],
+            )?,
+            ProgramEvent::CurveTrade(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO curve_trades VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.trader.to_string(),
+                    e.is_buy,
+                    e.amount as i64,
+                    e.tax_amount as i64,
+                    e.lamports as i64,
+                    e.tokens_sold as i64
+                ],
+            )?,
+            ProgramEvent::ReferralReward(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO referral_rewards VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.referrer.to_string(),
+                    e.user.to_string(),
+                    e.tax_amount as i64,
+                    e.amount as i64
+                ],
+            )?,
+            ProgramEvent::FeesHarvested(e) => self.conn.execute(
+                "INSERT OR IGNORE INTO fee_harvests VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    sig,
+                    idx,
+                    slot,
+                    e.timestamp,
+                    e.amount as i64,
+                    e.rewards as i64,
+                    e.lp_fund as i64,
+                    e.burned as i64
+                ],
+            )?,
+            _ => 0,
+        };
+        Ok(rows > 0)
+    }
+}
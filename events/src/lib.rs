@@ -0,0 +1,207 @@
+//! Typed decoding of the events `my_solami_token` emits with `emit!`.
+//!
+//! Anchor writes each event as a `Program data: <base64>` log line holding the
+//! 8-byte event discriminator followed by the borsh-encoded fields.
+//! [`parse_logs`] walks a transaction's log messages, keeps only the lines
+//! written while this program is the active frame, and decodes them.
+
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+pub use my_solami_token::events::{
+    BurnEvent, ConfidentialBalanceEvent, CurveMigratedEvent, CurveTradeEvent, EmissionEvent,
+    FeesHarvestedEvent, HolderEvent, LaunchPhaseChangedEvent, PoolAddressUpdatedEvent,
+    PresaleClaimEvent, PresaleContributionEvent, PresaleFinalizedEvent, PresaleRefundEvent,
+    ProposalCreatedEvent, ProposalExecutedEvent, ReferralClaimEvent, ReferralRegisteredEvent,
+    ReferralRewardEvent, SnapshotEvent, SupplyDiscrepancyEvent, SupplyFinalizedEvent, SwapEvent,
+    TransferEvent, VoteCastEvent, WhitelistEvent,
+};
+pub use my_solami_token::ID as PROGRAM_ID;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Any event emitted by the program
+pub enum ProgramEvent {
+    Transfer(TransferEvent),
+    Burn(BurnEvent),
+    Swap(SwapEvent),
+    Whitelist(WhitelistEvent),
+    Emission(EmissionEvent),
+    ConfidentialBalance(ConfidentialBalanceEvent),
+    FeesHarvested(FeesHarvestedEvent),
+    SupplyFinalized(SupplyFinalizedEvent),
+    SupplyDiscrepancy(SupplyDiscrepancyEvent),
+    PoolAddressUpdated(PoolAddressUpdatedEvent),
+    Holder(HolderEvent),
+    LaunchPhaseChanged(LaunchPhaseChangedEvent),
+    PresaleContribution(PresaleContributionEvent),
+    PresaleClaim(PresaleClaimEvent),
+    PresaleRefund(PresaleRefundEvent),
+    PresaleFinalized(PresaleFinalizedEvent),
+    CurveTrade(CurveTradeEvent),
+    CurveMigrated(CurveMigratedEvent),
+    ReferralRegistered(ReferralRegisteredEvent),
+    ReferralReward(ReferralRewardEvent),
+    ReferralClaim(ReferralClaimEvent),
+    ProposalCreated(ProposalCreatedEvent),
+    VoteCast(VoteCastEvent),
+    ProposalExecuted(ProposalExecutedEvent),
+    Snapshot(SnapshotEvent),
+}
+
+impl ProgramEvent {
+    /// Event name as declared in the program
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProgramEvent::Transfer(_) => "TransferEvent",
+            ProgramEvent::Burn(_) => "BurnEvent",
+            ProgramEvent::Swap(_) => "SwapEvent",
+            ProgramEvent::Whitelist(_) => "WhitelistEvent",
+            ProgramEvent::Emission(_) => "EmissionEvent",
+            ProgramEvent::ConfidentialBalance(_) => "ConfidentialBalanceEvent",
+            ProgramEvent::FeesHarvested(_) => "FeesHarvestedEvent",
+            ProgramEvent::SupplyFinalized(_) => "SupplyFinalizedEvent",
+            ProgramEvent::SupplyDiscrepancy(_) => "SupplyDiscrepancyEvent",
+            ProgramEvent::PoolAddressUpdated(_) => "PoolAddressUpdatedEvent",
+            ProgramEvent::Holder(_) => "HolderEvent",
+            ProgramEvent::LaunchPhaseChanged(_) => "LaunchPhaseChangedEvent",
+            ProgramEvent::PresaleContribution(_) => "PresaleContributionEvent",
+            ProgramEvent::PresaleClaim(_) => "PresaleClaimEvent",
+            ProgramEvent::PresaleRefund(_) => "PresaleRefundEvent",
+            ProgramEvent::PresaleFinalized(_) => "PresaleFinalizedEvent",
+            ProgramEvent::CurveTrade(_) => "CurveTradeEvent",
+            ProgramEvent::CurveMigrated(_) => "CurveMigratedEvent",
+            ProgramEvent::ReferralRegistered(_) => "ReferralRegisteredEvent",
+            ProgramEvent::ReferralReward(_) => "ReferralRewardEvent",
+            ProgramEvent::ReferralClaim(_) => "ReferralClaimEvent",
+            ProgramEvent::ProposalCreated(_) => "ProposalCreatedEvent",
+            ProgramEvent::VoteCast(_) => "VoteCastEvent",
+            ProgramEvent::ProposalExecuted(_) => "ProposalExecutedEvent",
+            ProgramEvent::Snapshot(_) => "SnapshotEvent",
+        }
+    }
+
+    /// Decode raw event bytes (discriminator + borsh payload)
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < 8 {
+            return None;
+        }
+        let (discriminator, mut payload) = data.split_at(8);
+        let event = match discriminator {
+            d if d == TransferEvent::DISCRIMINATOR => {
+                ProgramEvent::Transfer(TransferEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == BurnEvent::DISCRIMINATOR => {
+                ProgramEvent::Burn(BurnEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == SwapEvent::DISCRIMINATOR => {
+                ProgramEvent::Swap(SwapEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == WhitelistEvent::DISCRIMINATOR => {
+                ProgramEvent::Whitelist(WhitelistEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == EmissionEvent::DISCRIMINATOR => {
+                ProgramEvent::Emission(EmissionEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == ConfidentialBalanceEvent::DISCRIMINATOR => ProgramEvent::ConfidentialBalance(
+                ConfidentialBalanceEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == FeesHarvestedEvent::DISCRIMINATOR => {
+                ProgramEvent::FeesHarvested(FeesHarvestedEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == SupplyFinalizedEvent::DISCRIMINATOR => {
+                ProgramEvent::SupplyFinalized(SupplyFinalizedEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == SupplyDiscrepancyEvent::DISCRIMINATOR => ProgramEvent::SupplyDiscrepancy(
+                SupplyDiscrepancyEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == PoolAddressUpdatedEvent::DISCRIMINATOR => ProgramEvent::PoolAddressUpdated(
+                PoolAddressUpdatedEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == HolderEvent::DISCRIMINATOR => {
+                ProgramEvent::Holder(HolderEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == LaunchPhaseChangedEvent::DISCRIMINATOR => ProgramEvent::LaunchPhaseChanged(
+                LaunchPhaseChangedEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == PresaleContributionEvent::DISCRIMINATOR => ProgramEvent::PresaleContribution(
+                PresaleContributionEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == PresaleClaimEvent::DISCRIMINATOR => {
+                ProgramEvent::PresaleClaim(PresaleClaimEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == PresaleRefundEvent::DISCRIMINATOR => {
+                ProgramEvent::PresaleRefund(PresaleRefundEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == PresaleFinalizedEvent::DISCRIMINATOR => ProgramEvent::PresaleFinalized(
+                PresaleFinalizedEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == CurveTradeEvent::DISCRIMINATOR => {
+                ProgramEvent::CurveTrade(CurveTradeEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == CurveMigratedEvent::DISCRIMINATOR => {
+                ProgramEvent::CurveMigrated(CurveMigratedEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == ReferralRegisteredEvent::DISCRIMINATOR => ProgramEvent::ReferralRegistered(
+                ReferralRegisteredEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == ReferralRewardEvent::DISCRIMINATOR => {
+                ProgramEvent::ReferralReward(ReferralRewardEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == ReferralClaimEvent::DISCRIMINATOR => {
+                ProgramEvent::ReferralClaim(ReferralClaimEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == ProposalCreatedEvent::DISCRIMINATOR => {
+                ProgramEvent::ProposalCreated(ProposalCreatedEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == VoteCastEvent::DISCRIMINATOR => {
+                ProgramEvent::VoteCast(VoteCastEvent::deserialize(&mut payload).ok()?)
+            }
+            d if d == ProposalExecutedEvent::DISCRIMINATOR => ProgramEvent::ProposalExecuted(
+                ProposalExecutedEvent::deserialize(&mut payload).ok()?,
+            ),
+            d if d == SnapshotEvent::DISCRIMINATOR => {
+                ProgramEvent::Snapshot(SnapshotEvent::deserialize(&mut payload).ok()?)
+            }
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// Decode a single `Program data:` log line, regardless of which program wrote it
+pub fn parse_log_line(line: &str) -> Option<ProgramEvent> {
+    let encoded = line.strip_prefix(PROGRAM_DATA)?;
+    let data = STANDARD.decode(encoded.trim()).ok()?;
+    ProgramEvent::decode(&data)
+}
+
+/// Decode every event `program_id` emitted in a transaction's log messages, in order
+pub fn parse_logs<S: AsRef<str>>(program_id: &Pubkey, logs: &[S]) -> Vec<ProgramEvent> {
+    let program = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(rest) = line.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(id), Some("invoke")) => {
+                    stack.push(id);
+                    continue;
+                }
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    stack.pop();
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if stack.last() == Some(&program.as_str()) {
+            if let Some(event) = parse_log_line(line) {
+                events.push(event);
+            }
+        }
+    }
+    events
+}
@@ -0,0 +1,385 @@
+//! Drives random instruction sequences through an in-process runtime and
+//! checks that token supply is conserved: every token the mint reports is
+//! held by a tracked account, supply never grows after initialization, and
+//! `TokenState::total_supply` tracks the mint.
+//!
+//! Run with `cargo fuzz run instruction_sequence` from `programs/my_solami_token`.
+
+#![no_main]
+
+use std::sync::OnceLock;
+
+use anchor_lang::prelude::AccountInfo;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use my_solami_token_client::{accounts, instructions, pda};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account,
+};
+
+const TOTAL_SUPPLY: u64 = 1_000_000_000;
+const HOLDERS: usize = 4;
+const MAX_OPS: usize = 24;
+
+#[derive(Arbitrary, Debug)]
+enum Op {
+    Transfer { from: u8, to: u8, amount: u64 },
+    ManualBurn { amount: u64 },
+    UserBurn { holder: u8, amount: u64 },
+    SwapRewards { amount: u64 },
+    Freeze(bool),
+    AddToWhitelist { holder: u8 },
+    RemoveFromWhitelist { holder: u8 },
+    Warp { seconds: u16 },
+}
+
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> solana_sdk::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    my_solami_token::entry(program_id, accounts, data)
+}
+
+fn runtime() -> &'static tokio::runtime::Runtime {
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| tokio::runtime::Runtime::new().unwrap())
+}
+
+struct World {
+    ctx: ProgramTestContext,
+    mint: Pubkey,
+    /// Holder 0 is the admin
+    holders: Vec<Keypair>,
+    atas: Vec<Pubkey>,
+    rewards_pool: Pubkey,
+    lp_fund: Pubkey,
+    lp_pool: Pubkey,
+    staking_pool: Pubkey,
+    swap_wallet: Pubkey,
+}
+
+impl World {
+    async fn new() -> Self {
+        let program = ProgramTest::new(
+            "my_solami_token",
+            my_solami_token::ID,
+            processor!(process_instruction),
+        );
+        let ctx = program.start_with_context().await;
+        let holders: Vec<Keypair> = (0..HOLDERS).map(|_| Keypair::new()).collect();
+        let mint = Keypair::new();
+
+        let mut world = Self {
+            ctx,
+            mint: mint.pubkey(),
+            holders,
+            atas: Vec::new(),
+            rewards_pool: Pubkey::default(),
+            lp_fund: Pubkey::default(),
+            lp_pool: Pubkey::default(),
+            staking_pool: Pubkey::default(),
+            swap_wallet: Pubkey::default(),
+        };
+
+        let payer = world.ctx.payer.pubkey();
+        let rent = world.ctx.banks_client.get_rent().await.unwrap();
+        let mut setup = vec![
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &pda::mint_authority(&mint.pubkey()).0,
+                None,
+                0,
+            )
+            .unwrap(),
+        ];
+        for holder in &world.holders {
+            setup.push(system_instruction::transfer(
+                &payer,
+                &holder.pubkey(),
+                1_000_000_000,
+            ));
+            setup.push(create_associated_token_account(
+                &payer,
+                &holder.pubkey(),
+                &mint.pubkey(),
+                &spl_token::id(),
+            ));
+        }
+        world.send(&setup, &[&mint]).await.unwrap();
+        world.atas = world
+            .holders
+            .iter()
+            .map(|holder| get_associated_token_address(&holder.pubkey(), &world.mint))
+            .collect();
+
+        // Pools are owned by the admin so `prepare_rewards_swap` can move them
+        world.lp_pool = world.token_account().await;
+        world.rewards_pool = world.token_account().await;
+        world.lp_fund = world.token_account().await;
+        world.staking_pool = world.token_account().await;
+        world.swap_wallet = world.token_account().await;
+
+        let admin = world.holders[0].insecure_clone();
+        let init = instructions::initialize_token(
+            &instructions::InitializeTokenAccounts {
+                mint: world.mint,
+                admin: admin.pubkey(),
+                admin_token_account: world.atas[0],
+                lp_pool: world.lp_pool,
+                rewards_pool: world.rewards_pool,
+                lp_fund: world.lp_fund,
+                staking_pool: world.staking_pool,
+                swap_wallet: world.swap_wallet,
+                token_account: world.atas[0],
+                presale_escrow: None,
+                curve_reserve: None,
+                token_program: spl_token::id(),
+            },
+            TOTAL_SUPPLY,
+            vec![world.holders[HOLDERS - 1].pubkey()],
+            None,
+            0,
+        );
+        world.send(&[init], &[&admin]).await.unwrap();
+
+        world
+    }
+
+    async fn token_account(&mut self) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let ixs = [
+            system_instruction::create_account(
+                &self.ctx.payer.pubkey(),
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::id(),
+                &account.pubkey(),
+                &self.mint,
+                &self.holders[0].pubkey(),
+            )
+            .unwrap(),
+        ];
+        self.send(&ixs, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    async fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<(), ()> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.ctx.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.ctx.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .map_err(|_| ())
+    }
+
+    async fn balance(&mut self, key: Pubkey) -> u64 {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(key)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    async fn supply(&mut self) -> u64 {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(self.mint)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Mint::unpack(&account.data)
+            .unwrap()
+            .supply
+    }
+
+    async fn recorded_supply(&mut self) -> u64 {
+        let key = pda::token_state(&self.mint).0;
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(key)
+            .await
+            .unwrap()
+            .unwrap();
+        accounts::decode_token_state(&account.data)
+            .unwrap()
+            .total_supply
+    }
+
+    async fn tracked_total(&mut self) -> u64 {
+        let mut keys = self.atas.clone();
+        keys.extend([
+            self.lp_pool,
+            self.rewards_pool,
+            self.lp_fund,
+            self.staking_pool,
+            self.swap_wallet,
+        ]);
+        let mut total = 0u64;
+        for key in keys {
+            total += self.balance(key).await;
+        }
+        total
+    }
+
+    async fn apply(&mut self, op: &Op) {
+        let admin = self.holders[0].insecure_clone();
+        let token_program = spl_token::id();
+        // Failures are expected (limits, freezes, whitelist size); only state matters
+        let _ = match *op {
+            Op::Transfer { from, to, amount } => {
+                let from = from as usize % HOLDERS;
+                let to = to as usize % HOLDERS;
+                let balance = self.balance(self.atas[from]).await;
+                let owner = self.holders[from].insecure_clone();
+                let ix = instructions::transfer_tokens(
+                    &instructions::TransferTokensAccounts {
+                        mint: self.mint,
+                        sender: self.atas[from],
+                        receiver: self.atas[to],
+                        authority: owner.pubkey(),
+                        rewards_pool: self.rewards_pool,
+                        lp_fund: self.lp_fund,
+                        lp_pool: self.lp_pool,
+                        token_program,
+                        receiver_record: None,
+                        sender_record: None,
+                        rent_recipient: None,
+                        payer: None,
+                        sender_referral: None,
+                        referrer_stats: None,
+                        referral_vault: None,
+                        loyalty_tiers: None,
+                        sender_checkpoints: None,
+                        receiver_checkpoints: None,
+                    },
+                    amount % (balance + 1),
+                );
+                self.send(&[ix], &[&owner]).await
+            }
+            Op::ManualBurn { amount } => {
+                let balance = self.balance(self.atas[0]).await;
+                let ix = instructions::manual_burn(
+                    &self.mint,
+                    &admin.pubkey(),
+                    &self.atas[0],
+                    &token_program,
+                    None,
+                    amount % (balance + 1),
+                );
+                self.send(&[ix], &[&admin]).await
+            }
+            Op::UserBurn { holder, amount } => {
+                let holder = holder as usize % HOLDERS;
+                let signer = self.holders[holder].insecure_clone();
+                let balance = self.balance(self.atas[holder]).await;
+                let ix = instructions::user_burn(
+                    &self.mint,
+                    &signer.pubkey(),
+                    &self.atas[holder],
+                    &token_program,
+                    None,
+                    amount % (balance + 1),
+                );
+                self.send(&[ix], &[&signer]).await
+            }
+            Op::SwapRewards { amount } => {
+                let balance = self.balance(self.rewards_pool).await;
+                let ix = instructions::prepare_rewards_swap(
+                    &self.mint,
+                    &admin.pubkey(),
+                    &self.rewards_pool,
+                    &self.swap_wallet,
+                    &token_program,
+                    amount % (balance + 1),
+                );
+                self.send(&[ix], &[&admin]).await
+            }
+            Op::Freeze(freeze) => {
+                let ix = instructions::freeze_contract(&self.mint, &admin.pubkey(), freeze);
+                self.send(&[ix], &[&admin]).await
+            }
+            Op::AddToWhitelist { holder } => {
+                let wallet = self.holders[holder as usize % HOLDERS].pubkey();
+                let ix = instructions::add_to_whitelist(&self.mint, &admin.pubkey(), wallet);
+                self.send(&[ix], &[&admin]).await
+            }
+            Op::RemoveFromWhitelist { holder } => {
+                let wallet = self.holders[holder as usize % HOLDERS].pubkey();
+                let ix = instructions::remove_from_whitelist(&self.mint, &admin.pubkey(), wallet);
+                self.send(&[ix], &[&admin]).await
+            }
+            Op::Warp { seconds } => {
+                let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+                clock.unix_timestamp += seconds as i64;
+                self.ctx.set_sysvar(&clock);
+                Ok(())
+            }
+        };
+    }
+}
+
+fuzz_target!(|ops: Vec<Op>| {
+    runtime().block_on(async {
+        let mut world = World::new().await;
+        let mut supply = world.supply().await;
+        assert!(supply <= TOTAL_SUPPLY);
+
+        for op in ops.iter().take(MAX_OPS) {
+            world.apply(op).await;
+
+            let new_supply = world.supply().await;
+            assert!(new_supply <= supply, "supply grew after {op:?}");
+            assert_eq!(
+                world.tracked_total().await,
+                new_supply,
+                "tokens escaped tracked accounts after {op:?}"
+            );
+            assert_eq!(
+                world.recorded_supply().await,
+                new_supply,
+                "TokenState supply drifted from the mint after {op:?}"
+            );
+            supply = new_supply;
+        }
+    });
+});
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid whitelist size")]
+    InvalidWhitelistSize,
+    #[msg("Insufficient balance for operation")]
+    InsufficientBalance,
+    #[msg("Trading not enabled yet")]
+    TradingNotEnabled,
+    #[msg("Whitelist is full")]
+    WhitelistFull,
+    #[msg("Address not in whitelist")]
+    NotInWhitelist,
+    #[msg("Unauthorized action")]
+    Unauthorized,
+    #[msg("Transfer cooldown active")]
+    TransferCooldown,
+    #[msg("Transfer amount exceeds limit")]
+    TransferLimitExceeded,
+    #[msg("Invalid new admin address")]
+    InvalidAdminAddress,
+    #[msg("Invalid supply")]
+    InvalidSupply,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Contract is frozen")]
+    ContractFrozen,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Arithmetic underflow")]
+    ArithmeticUnderflow,
+    #[msg("Required account not found")]
+    AccountNotFound,
+    #[msg("Insufficient accounts provided")]
+    InsufficientAccounts,
+    #[msg("Account mismatch")]
+    AccountMismatch,
+    #[msg("Token supply has been finalized")]
+    SupplyFinalized,
+    #[msg("Emission schedule not configured")]
+    EmissionNotConfigured,
+    #[msg("Emission epoch has not elapsed")]
+    EmissionEpochNotElapsed,
+    #[msg("Maximum supply reached")]
+    MaxSupplyReached,
+    #[msg("Invalid emission configuration")]
+    InvalidEmissionConfig,
+    #[msg("Mint transfer hook is not configured for this program")]
+    InvalidTransferHook,
+    #[msg("Transfer hook invoked outside of a transfer")]
+    NotTransferring,
+    #[msg("Taxed transfers must go through transfer_tokens")]
+    TaxedTransferRequired,
+    #[msg("Missing withdraw proof context account")]
+    MissingProofContext,
+    #[msg("Operation not supported by the configured tax backend")]
+    InvalidTaxBackend,
+    #[msg("Invalid transfer fee configuration")]
+    InvalidTransferFee,
+    #[msg("Holder record accounts are incomplete")]
+    MissingHolderAccounts,
+    #[msg("Only whitelisted wallets can trade in the whitelist-only phase")]
+    WhitelistOnlyPhase,
+    #[msg("Invalid launch schedule")]
+    InvalidLaunchSchedule,
+    #[msg("Launch schedule can no longer change once trading is public")]
+    LaunchComplete,
+    #[msg("Invalid launch tax configuration")]
+    InvalidLaunchTax,
+    #[msg("Launch tax can only change before trading opens")]
+    LaunchTaxLocked,
+    #[msg("Invalid presale configuration")]
+    InvalidPresale,
+    #[msg("Presale is not accepting contributions")]
+    PresaleNotActive,
+    #[msg("Contribution exceeds the presale or per-wallet cap")]
+    PresaleCapExceeded,
+    #[msg("Presale has not ended")]
+    PresaleNotEnded,
+    #[msg("Presale soft cap was not met")]
+    PresaleSoftCapNotMet,
+    #[msg("Presale soft cap was met, claim tokens instead")]
+    PresaleSoftCapMet,
+    #[msg("Presale already finalized")]
+    PresaleFinalized,
+    #[msg("Invalid bonding curve configuration")]
+    InvalidBondingCurve,
+    #[msg("Bonding curve has migrated to the liquidity pool")]
+    CurveMigrated,
+    #[msg("Trade exceeds the bonding curve supply")]
+    CurveSupplyExceeded,
+    #[msg("Price moved beyond the slippage limit")]
+    SlippageExceeded,
+    #[msg("Invalid referral configuration")]
+    InvalidReferralConfig,
+    #[msg("A wallet cannot refer itself")]
+    SelfReferral,
+    #[msg("Referral accounts are incomplete")]
+    MissingReferralAccounts,
+    #[msg("No referral rewards to claim")]
+    NothingToClaim,
+    #[msg("Invalid loyalty tiers")]
+    InvalidLoyaltyTiers,
+    #[msg("Invalid governance configuration")]
+    InvalidGovernanceConfig,
+    #[msg("Not enough voting power at the snapshot")]
+    InsufficientVotingPower,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    #[msg("Proposal did not pass or voting is still open")]
+    ProposalNotPassed,
+    #[msg("Proposal already executed")]
+    ProposalExecuted,
+    #[msg("Checkpoint accounts are incomplete")]
+    MissingCheckpointAccounts,
+    #[msg("Snapshot does not exist")]
+    InvalidSnapshot,
+    #[msg("Checkpoint for this snapshot was pruned")]
+    SnapshotPruned,
+    #[msg("Delegated amount is below the transfer amount")]
+    InsufficientAllowance,
+    #[msg("Batch transfers must match their recipient accounts, up to the batch limit")]
+    InvalidBatchTransfer,
+    #[msg("Only whitelisted wallets can hold confidential balances")]
+    ConfidentialNotWhitelisted,
+    #[msg("Address already in whitelist")]
+    AlreadyWhitelisted,
+    #[msg("Governance needs the transfer hook to record direct transfers")]
+    GovernanceNeedsTransferHook,
+    #[msg("Balance at the snapshot is unproven without checkpoints opened before it")]
+    BalanceNotProvable,
+    #[msg("Program pool balances are not covered by snapshots")]
+    PoolNotSnapshotted,
+    #[msg("Referrer must be a wallet")]
+    InvalidReferrer,
+}
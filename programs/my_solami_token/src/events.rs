@@ -0,0 +1,247 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{LaunchPhase, PoolKind, ProposalAction};
+
+#[event]
+pub struct SwapEvent {
+    pub amount: u64,
+    pub timestamp: i64,
+    pub pool: Pubkey,
+}
+
+#[event]
+pub struct TransferEvent {
+    pub sender: Pubkey,
+    pub receiver: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+    pub tax_amount: u64,
+    /// Tax rate applied, in basis points
+    pub tax_rate_bps: u16,
+}
+
+#[event]
+pub struct BurnEvent {
+    pub burner: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+    pub new_total_supply: u64,
+}
+
+#[event]
+pub struct WhitelistEvent {
+    pub wallet: Pubkey,
+    pub is_added: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EmissionEvent {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub epoch_time: i64,
+    pub total_emitted: u64,
+    pub new_total_supply: u64,
+}
+
+#[event]
+pub struct ConfidentialBalanceEvent {
+    pub owner: Pubkey,
+    pub token_account: Pubkey,
+    pub amount: u64,
+    pub tax_amount: u64,
+    pub is_deposit: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesHarvestedEvent {
+    pub amount: u64,
+    pub rewards: u64,
+    pub lp_fund: u64,
+    pub burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SupplyFinalizedEvent {
+    pub mint: Pubkey,
+    pub final_supply: u64,
+    pub freeze_authority_revoked: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolAddressUpdatedEvent {
+    pub mint: Pubkey,
+    pub pool: PoolKind,
+    pub old_address: Pubkey,
+    pub new_address: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct HolderEvent {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub is_added: bool,
+    pub holder_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchPhaseChangedEvent {
+    pub mint: Pubkey,
+    pub previous_phase: LaunchPhase,
+    pub phase: LaunchPhase,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SupplyDiscrepancyEvent {
+    pub mint: Pubkey,
+    pub recorded_supply: u64,
+    pub actual_supply: u64,
+    pub recorded_burned: u64,
+    pub recorded_liquidity_pool: u64,
+    pub actual_liquidity_pool: u64,
+    pub recorded_rewards_pool: u64,
+    pub actual_rewards_pool: u64,
+    pub recorded_staking_pool: u64,
+    pub actual_staking_pool: u64,
+    pub corrected: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleContributionEvent {
+    pub mint: Pubkey,
+    pub contributor: Pubkey,
+    /// Lamports added by this contribution
+    pub amount: u64,
+    pub total_contributed: u64,
+    pub total_raised: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleClaimEvent {
+    pub mint: Pubkey,
+    pub contributor: Pubkey,
+    pub contributed: u64,
+    pub tokens: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleRefundEvent {
+    pub mint: Pubkey,
+    pub contributor: Pubkey,
+    /// Lamports returned
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PresaleFinalizedEvent {
+    pub mint: Pubkey,
+    pub soft_cap_met: bool,
+    /// Lamports paid to the admin
+    pub proceeds: u64,
+    /// Tranche tokens returned to the admin
+    pub unsold_tokens: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveTradeEvent {
+    pub mint: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    /// Tokens taken from or returned to the curve, before tax
+    pub amount: u64,
+    pub tax_amount: u64,
+    /// Lamports paid in or out
+    pub lamports: u64,
+    pub tokens_sold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveMigratedEvent {
+    pub mint: Pubkey,
+    /// Reserve tokens moved to `lp_pool`
+    pub tokens: u64,
+    /// `TokenState::admin`, paid the SOL reserve
+    pub payout: Pubkey,
+    pub payout_lamports: u64,
+    pub market_cap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRegisteredEvent {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub referrer: Pubkey,
+    pub referred_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRewardEvent {
+    pub mint: Pubkey,
+    pub referrer: Pubkey,
+    pub user: Pubkey,
+    /// Tax the referred wallet paid on the transfer
+    pub tax_amount: u64,
+    /// Share of it credited to the referrer
+    pub amount: u64,
+    pub claimable: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralClaimEvent {
+    pub mint: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub mint: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub snapshot_id: u64,
+    pub voting_ends: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteCastEvent {
+    pub mint: Pubkey,
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub mint: Pubkey,
+    pub proposal_id: u64,
+    pub action: ProposalAction,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SnapshotEvent {
+    pub mint: Pubkey,
+    pub snapshot_id: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,374 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{
+    allocate_tax, calculate_transfer_amounts, check_referral, close_emptied_holder_record,
+    credit_referrer, open_holder_record, record_checkpoint, set_transfer_hook_program,
+    track_holder_balance, transfer_within_program, AllocateTaxAccounts,
+};
+use crate::errors::ErrorCode;
+use crate::events::{HolderEvent, LaunchPhaseChangedEvent, TransferEvent};
+use crate::state::{
+    BatchTransferItem, HolderRecord, LoyaltyTiers, ReferrerStats, TaxBackend, TokenState,
+    Whitelist, MAX_BATCH_TRANSFERS,
+};
+use crate::tokenomics;
+
+/// Remaining accounts ahead of the recipients: the sender's `HolderRecord` and
+/// `BalanceCheckpoints` PDAs
+const SENDER_ACCOUNTS: usize = 2;
+/// Remaining accounts per transfer: the recipient token account followed by its
+/// `HolderRecord` and `BalanceCheckpoints` PDAs
+const ACCOUNTS_PER_RECIPIENT: usize = 3;
+
+#[derive(Accounts)]
+pub struct BatchTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = rewards_pool,
+        has_one = lp_fund,
+        has_one = lp_pool,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, token::mint = mint, token::authority = authority)]
+    pub sender: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_fund: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_pool: InterfaceAccount<'info, TokenAccount>,
+    /// Owner of `sender`
+    pub authority: Signer<'info>,
+    /// CHECK: PDA that toggles the transfer hook around the program's transfers
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump = token_state.whitelist_bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Discounts every transfer's tax for a sender with a long enough continuous hold
+    #[account(
+        seeds = [b"loyalty_tiers", mint.key().as_ref()],
+        bump = loyalty_tiers.bump,
+    )]
+    pub loyalty_tiers: Option<Account<'info, LoyaltyTiers>>,
+    /// Pays for new holder records and checkpoint accounts
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+    /// CHECK: Checked against the sender record's `rent_payer` once the sender is emptied
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: `ReferralRecord` PDA of the sender's owner, required once referrals
+    /// are configured; the stats and vault are required when it exists
+    pub sender_referral: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub referrer_stats: Option<Account<'info, ReferrerStats>>,
+    #[account(mut, token::mint = mint)]
+    pub referral_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchTransfer<'info>>,
+    transfers: Vec<BatchTransferItem>,
+) -> Result<()> {
+    require!(
+        !transfers.is_empty()
+            && transfers.len() <= MAX_BATCH_TRANSFERS
+            && ctx.remaining_accounts.len()
+                == SENDER_ACCOUNTS + transfers.len() * ACCOUNTS_PER_RECIPIENT,
+        ErrorCode::InvalidBatchTransfer
+    );
+    require!(
+        !ctx.accounts.token_state.is_frozen,
+        ErrorCode::ContractFrozen
+    );
+    let (sender_accounts, recipient_accounts) = ctx.remaining_accounts.split_at(SENDER_ACCOUNTS);
+    let (sender_record, sender_checkpoints) = (&sender_accounts[0], &sender_accounts[1]);
+
+    let mut total: u64 = 0;
+    for item in &transfers {
+        require!(item.amount > 0, ErrorCode::InvalidAmount);
+        total = total
+            .checked_add(item.amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+    require!(
+        ctx.accounts.sender.amount >= total,
+        ErrorCode::InsufficientBalance
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let mint_key = ctx.accounts.mint.key();
+    if let Some(previous_phase) = ctx.accounts.token_state.advance_launch_phase(now) {
+        emit!(LaunchPhaseChangedEvent {
+            mint: mint_key,
+            previous_phase,
+            phase: ctx.accounts.token_state.launch_phase,
+            timestamp: now,
+        });
+    }
+
+    let discount_bps = loyalty_discount(ctx.accounts, sender_record, now)?;
+    let referred = check_referral(
+        &ctx.accounts.token_state,
+        &mint_key,
+        &ctx.accounts.sender.owner,
+        ctx.accounts.sender_referral.as_deref(),
+        ctx.accounts.referrer_stats.as_deref(),
+        ctx.accounts.referral_vault.as_ref().map(|vault| vault.key()).as_ref(),
+    )?;
+
+    // Checkpoint every balance before the batch moves it
+    let payer = ctx.accounts.payer.as_deref();
+    let system_program = ctx.accounts.system_program.as_deref();
+    record_checkpoint(
+        &ctx.accounts.token_state,
+        Some(sender_checkpoints),
+        &ctx.accounts.sender,
+        payer,
+        system_program,
+    )?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+    let hook_enabled = ctx.accounts.token_state.transfer_hook_enabled;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            None,
+        )?;
+    }
+
+    let sender_whitelisted = ctx.accounts.whitelist.contains(&ctx.accounts.sender.owner);
+    let mut total_tax: u64 = 0;
+    let (mut lp_pool_received, mut rewards_pool_received) = (false, false);
+    let recipients = recipient_accounts.chunks(ACCOUNTS_PER_RECIPIENT);
+    for (item, entry) in transfers.iter().zip(recipients) {
+        let recipient_info = &entry[0];
+        require_keys_eq!(
+            recipient_info.key(),
+            item.recipient,
+            ErrorCode::InvalidBatchTransfer
+        );
+        let recipient = InterfaceAccount::<TokenAccount>::try_from(recipient_info)?;
+        require_keys_eq!(recipient.mint, mint_key, ErrorCode::AccountMismatch);
+        record_checkpoint(
+            &ctx.accounts.token_state,
+            Some(&entry[2]),
+            &recipient,
+            payer,
+            system_program,
+        )?;
+
+        let whitelisted = sender_whitelisted || ctx.accounts.whitelist.contains(&recipient.owner);
+        ctx.accounts
+            .token_state
+            .check_launch_phase(now, item.amount, whitelisted)?;
+
+        let (net_amount, tax_amount, tax_rate_bps) = match ctx.accounts.token_state.tax_backend {
+            TaxBackend::Program => calculate_transfer_amounts(
+                &ctx.accounts.token_state,
+                &ctx.accounts.whitelist,
+                &recipient.owner,
+                item.amount,
+                now,
+                discount_bps,
+            )?,
+            TaxBackend::TransferFee => (item.amount, 0, 0),
+        };
+
+        transfer_within_program(
+            &ctx.accounts.sender.to_account_info(),
+            recipient_info,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            &[],
+            net_amount,
+        )?;
+        total_tax = total_tax
+            .checked_add(tax_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+        lp_pool_received |= item.recipient == ctx.accounts.lp_pool.key();
+        rewards_pool_received |= item.recipient == ctx.accounts.rewards_pool.key();
+
+        emit!(TransferEvent {
+            sender: ctx.accounts.sender.key(),
+            receiver: item.recipient,
+            amount: net_amount,
+            timestamp: now,
+            tax_amount,
+            tax_rate_bps,
+        });
+    }
+
+    // One split and one set of pool transfers for the whole batch
+    let split = if referred {
+        tokenomics::split_tax_with_referral(total_tax, ctx.accounts.token_state.referral_bps)
+    } else {
+        tokenomics::split_tax(total_tax)
+    };
+    if total_tax > 0 {
+        allocate_tax(
+            AllocateTaxAccounts {
+                sender: ctx.accounts.sender.clone(),
+                rewards_pool: ctx.accounts.rewards_pool.clone(),
+                lp_fund: ctx.accounts.lp_fund.clone(),
+                referral_vault: ctx.accounts.referral_vault.clone(),
+                sender_authority: ctx.accounts.authority.to_account_info(),
+                mint: ctx.accounts.mint.clone(),
+                token_program: ctx.accounts.token_program.clone(),
+            },
+            &[],
+            &split,
+        )?;
+    }
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            Some(crate::ID),
+        )?;
+    }
+
+    if referred && total_tax > 0 {
+        let stats = ctx.accounts.referrer_stats
+            .as_mut()
+            .ok_or(ErrorCode::MissingReferralAccounts)?;
+        credit_referrer(
+            stats,
+            &ctx.accounts.sender.owner,
+            total_tax,
+            split.referral,
+            now,
+        )?;
+    }
+
+    // Start or break each account's continuous hold at its final balance, closing
+    // the sender's record once it is emptied and opening new recipients' records
+    track_holder_balance(
+        &ctx.accounts.token_state,
+        Some(sender_record),
+        &mut ctx.accounts.sender,
+        now,
+    )?;
+    close_emptied_holder_record(
+        &mut ctx.accounts.token_state,
+        Some(sender_record),
+        ctx.accounts.rent_recipient.as_deref(),
+        &ctx.accounts.sender,
+        now,
+    )?;
+    for entry in recipient_accounts.chunks(ACCOUNTS_PER_RECIPIENT) {
+        let mut recipient = InterfaceAccount::<TokenAccount>::try_from(&entry[0])?;
+        open_recipient_record(ctx.accounts, &entry[1], &recipient, now)?;
+        track_holder_balance(
+            &ctx.accounts.token_state,
+            Some(&entry[1]),
+            &mut recipient,
+            now,
+        )?;
+    }
+
+    let token_state = &mut ctx.accounts.token_state;
+    token_state.total_transactions += transfers.len() as u64;
+    token_state.record_tax(total_tax, split.burn)?;
+
+    // Update TVL-related balances after the batch
+    if lp_pool_received {
+        ctx.accounts.lp_pool.reload()?;
+        token_state.update_liquidity_pool(ctx.accounts.lp_pool.amount)?;
+    }
+    if rewards_pool_received {
+        ctx.accounts.rewards_pool.reload()?;
+        token_state.update_rewards_pool(ctx.accounts.rewards_pool.amount)?;
+    }
+
+    Ok(())
+}
+
+/// Open the holder record of a first-time recipient, paid by `payer`
+fn open_recipient_record<'info>(
+    accounts: &mut BatchTransfer<'info>,
+    record: &AccountInfo<'info>,
+    recipient: &InterfaceAccount<'info, TokenAccount>,
+    now: i64,
+) -> Result<()> {
+    let (Some(payer), Some(system_program)) = (&accounts.payer, &accounts.system_program)
+    else {
+        return err!(ErrorCode::MissingHolderAccounts);
+    };
+    let mint = accounts.mint.key();
+    let opened = open_holder_record(
+        record,
+        &payer.to_account_info(),
+        &system_program.to_account_info(),
+        &mint,
+        &recipient.key(),
+        &recipient.owner,
+        now,
+    )?;
+    if opened {
+        let token_state = &mut accounts.token_state;
+        token_state.holder_count = token_state.holder_count
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        emit!(HolderEvent {
+            mint,
+            owner: recipient.owner,
+            is_added: true,
+            holder_count: token_state.holder_count,
+            timestamp: now,
+        });
+    }
+    Ok(())
+}
+
+/// Loyalty discount earned by the sender's continuous hold, applied to every transfer
+fn loyalty_discount(accounts: &BatchTransfer, record: &AccountInfo, now: i64) -> Result<u16> {
+    if accounts.token_state.loyalty_min_balance == 0 {
+        return Ok(0);
+    }
+
+    let (address, _) = HolderRecord::find_address(&accounts.mint.key(), &accounts.sender.key());
+    require_keys_eq!(record.key(), address, ErrorCode::AccountMismatch);
+    if record.data_is_empty() {
+        return Ok(0);
+    }
+    let holder = HolderRecord::try_deserialize(&mut &record.try_borrow_data()?[..])?;
+
+    let since = accounts.token_state.loyalty_since;
+    let (Some(tiers), Some(held_for)) = (&accounts.loyalty_tiers, holder.held_for(now, since))
+    else {
+        return Ok(0);
+    };
+    Ok(tokenomics::loyalty_discount_bps(
+        &tiers.tiers,
+        accounts.sender.amount,
+        held_for,
+    ))
+}
@@ -0,0 +1,467 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{
+    allocate_tax, calculate_transfer_amounts, close_emptied_holder_record, record_checkpoint,
+    set_transfer_hook_program, track_holder_balance, transfer_within_program,
+    AllocateTaxAccounts,
+};
+use crate::errors::ErrorCode;
+use crate::events::{CurveMigratedEvent, CurveTradeEvent};
+use crate::state::{BondingCurve, CurveShape, TaxBackend, TokenState, Whitelist};
+use crate::tokenomics::{self, MAX_CURVE_STEPS};
+
+#[derive(Accounts)]
+pub struct ConfigureBondingCurve<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = curve_reserve,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + BondingCurve::SIZE,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(token::mint = mint)]
+    pub curve_reserve: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CurveTrade<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = curve_reserve,
+        has_one = lp_pool,
+        has_one = rewards_pool,
+        has_one = lp_fund,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump = token_state.whitelist_bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    #[account(
+        mut,
+        seeds = [b"bonding_curve", mint.key().as_ref()],
+        bump = bonding_curve.bump,
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+    #[account(mut, token::mint = mint)]
+    pub curve_reserve: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub trader: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = trader,
+    )]
+    pub trader_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_fund: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: `token_state.admin`, paid the SOL reserve on migration to seed the pool
+    #[account(mut, address = token_state.admin @ ErrorCode::AccountMismatch)]
+    pub migration_payout: UncheckedAccount<'info>,
+    /// CHECK: PDA holding the transfer hook authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of `trader_token_account`, created with
+    /// `trader` if missing
+    #[account(mut)]
+    pub trader_checkpoints: Option<UncheckedAccount<'info>>,
+    /// CHECK: `HolderRecord` PDA of `trader_token_account`, required while loyalty
+    /// tiers are set and when a sell empties the account
+    #[account(mut)]
+    pub trader_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: `rent_payer` of `trader_record`, refunded when the balance is emptied
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+}
+
+pub(crate) fn configure_bonding_curve(
+    ctx: Context<ConfigureBondingCurve>,
+    shape: CurveShape,
+    migration_market_cap: u64,
+) -> Result<()> {
+    let curve_supply = ctx.accounts.curve_reserve.amount;
+
+    require!(
+        shape.start_price > 0 && shape.step_size > 0 && migration_market_cap > 0,
+        ErrorCode::InvalidBondingCurve
+    );
+    require!(
+        curve_supply / shape.step_size <= MAX_CURVE_STEPS,
+        ErrorCode::InvalidBondingCurve
+    );
+    // Selling out the whole reserve must stay within u64 lamports
+    require!(
+        tokenomics::curve_buy_cost(&shape, 0, curve_supply).is_some(),
+        ErrorCode::InvalidBondingCurve
+    );
+
+    ctx.accounts.bonding_curve.set_inner(BondingCurve {
+        mint: ctx.accounts.mint.key(),
+        shape,
+        curve_supply,
+        tokens_sold: 0,
+        sol_reserve: 0,
+        migration_market_cap,
+        migrated: false,
+        bump: ctx.bumps.bonding_curve,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn buy_on_curve(ctx: Context<CurveTrade>, amount: u64, max_lamports: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    check_trade(&ctx, now, amount)?;
+
+    let curve = &ctx.accounts.bonding_curve;
+    let tokens_sold = curve
+        .tokens_sold
+        .checked_add(amount)
+        .filter(|sold| *sold <= curve.curve_supply)
+        .ok_or(ErrorCode::CurveSupplyExceeded)?;
+    let cost = tokenomics::curve_buy_cost(&curve.shape, curve.tokens_sold, amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(cost <= max_lamports, ErrorCode::SlippageExceeded);
+
+    // The bonding curve PDA's own lamports are the SOL reserve
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.trader.to_account_info(),
+                to: ctx.accounts.bonding_curve.to_account_info(),
+            },
+        ),
+        cost,
+    )?;
+
+    let (net_amount, tax_amount) = trade_tax(&ctx, amount, now)?;
+    record_trader_checkpoint(&ctx)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let curve_seeds: &[&[&[u8]]] = &[&[
+        b"bonding_curve",
+        mint_key.as_ref(),
+        &[ctx.accounts.bonding_curve.bump],
+    ]];
+
+    toggle_hook(&ctx, None)?;
+
+    transfer_within_program(
+        &ctx.accounts.curve_reserve.to_account_info(),
+        &ctx.accounts.trader_token_account.to_account_info(),
+        &ctx.accounts.bonding_curve.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        curve_seeds,
+        net_amount,
+    )?;
+
+    let split = tokenomics::split_tax(tax_amount);
+    if tax_amount > 0 {
+        allocate_tax(
+            AllocateTaxAccounts {
+                sender: ctx.accounts.curve_reserve.clone(),
+                rewards_pool: ctx.accounts.rewards_pool.clone(),
+                lp_fund: ctx.accounts.lp_fund.clone(),
+                referral_vault: None,
+                sender_authority: ctx.accounts.bonding_curve.to_account_info(),
+                mint: ctx.accounts.mint.clone(),
+                token_program: ctx.accounts.token_program.clone(),
+            },
+            curve_seeds,
+            &split,
+        )?;
+    }
+
+    let curve = &mut ctx.accounts.bonding_curve;
+    curve.tokens_sold = tokens_sold;
+    curve.sol_reserve = curve
+        .sol_reserve
+        .checked_add(cost)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    ctx.accounts.token_state.total_transactions += 1;
+    ctx.accounts
+        .token_state
+        .record_tax(tax_amount, split.burn)?;
+
+    track_holder_balance(
+        &ctx.accounts.token_state,
+        ctx.accounts.trader_record.as_deref(),
+        &mut ctx.accounts.trader_token_account,
+        now,
+    )?;
+
+    emit!(CurveTradeEvent {
+        mint: mint_key,
+        trader: ctx.accounts.trader.key(),
+        is_buy: true,
+        amount,
+        tax_amount,
+        lamports: cost,
+        tokens_sold,
+        timestamp: now,
+    });
+
+    migrate_if_ready(ctx.accounts, curve_seeds, now)?;
+
+    toggle_hook(&ctx, Some(crate::ID))
+}
+
+pub(crate) fn sell_on_curve(
+    ctx: Context<CurveTrade>,
+    amount: u64,
+    min_lamports: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    check_trade(&ctx, now, amount)?;
+
+    require!(
+        ctx.accounts.trader_token_account.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+
+    // Only the tokens that reach the reserve move the curve back down
+    let (net_amount, tax_amount) = trade_tax(&ctx, amount, now)?;
+
+    let curve = &ctx.accounts.bonding_curve;
+    let tokens_sold = curve
+        .tokens_sold
+        .checked_sub(net_amount)
+        .ok_or(ErrorCode::CurveSupplyExceeded)?;
+    let proceeds = tokenomics::curve_sell_proceeds(&curve.shape, curve.tokens_sold, net_amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(proceeds >= min_lamports, ErrorCode::SlippageExceeded);
+
+    record_trader_checkpoint(&ctx)?;
+    toggle_hook(&ctx, None)?;
+
+    transfer_within_program(
+        &ctx.accounts.trader_token_account.to_account_info(),
+        &ctx.accounts.curve_reserve.to_account_info(),
+        &ctx.accounts.trader.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &[],
+        net_amount,
+    )?;
+
+    let split = tokenomics::split_tax(tax_amount);
+    if tax_amount > 0 {
+        allocate_tax(
+            AllocateTaxAccounts {
+                sender: ctx.accounts.trader_token_account.clone(),
+                rewards_pool: ctx.accounts.rewards_pool.clone(),
+                lp_fund: ctx.accounts.lp_fund.clone(),
+                referral_vault: None,
+                sender_authority: ctx.accounts.trader.to_account_info(),
+                mint: ctx.accounts.mint.clone(),
+                token_program: ctx.accounts.token_program.clone(),
+            },
+            &[],
+            &split,
+        )?;
+    }
+
+    toggle_hook(&ctx, Some(crate::ID))?;
+
+    let curve = &mut ctx.accounts.bonding_curve;
+    curve.tokens_sold = tokens_sold;
+    curve.sol_reserve = curve
+        .sol_reserve
+        .checked_sub(proceeds)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    curve.sub_lamports(proceeds)?;
+    ctx.accounts.trader.add_lamports(proceeds)?;
+
+    ctx.accounts.token_state.total_transactions += 1;
+    ctx.accounts
+        .token_state
+        .record_tax(tax_amount, split.burn)?;
+
+    track_holder_balance(
+        &ctx.accounts.token_state,
+        ctx.accounts.trader_record.as_deref(),
+        &mut ctx.accounts.trader_token_account,
+        now,
+    )?;
+    close_emptied_holder_record(
+        &mut ctx.accounts.token_state,
+        ctx.accounts.trader_record.as_deref(),
+        ctx.accounts.rent_recipient.as_deref(),
+        &ctx.accounts.trader_token_account,
+        now,
+    )?;
+
+    emit!(CurveTradeEvent {
+        mint: ctx.accounts.mint.key(),
+        trader: ctx.accounts.trader.key(),
+        is_buy: false,
+        amount,
+        tax_amount,
+        lamports: proceeds,
+        tokens_sold,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Checks shared by buys and sells
+fn check_trade(ctx: &Context<CurveTrade>, now: i64, amount: u64) -> Result<()> {
+    let whitelisted = ctx.accounts.whitelist.contains(&ctx.accounts.trader.key());
+    ctx.accounts
+        .token_state
+        .check_launch_phase(now, amount, whitelisted)?;
+
+    require!(
+        !ctx.accounts.token_state.is_frozen,
+        ErrorCode::ContractFrozen
+    );
+    require!(
+        !ctx.accounts.bonding_curve.migrated,
+        ErrorCode::CurveMigrated
+    );
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    Ok(())
+}
+
+/// Split a curve trade into (net, tax) for the trader
+fn trade_tax(ctx: &Context<CurveTrade>, amount: u64, now: i64) -> Result<(u64, u64)> {
+    // With the transfer fee backend Token-2022 withholds the tax natively
+    match ctx.accounts.token_state.tax_backend {
+        TaxBackend::Program => {
+            let (net, tax, _) = calculate_transfer_amounts(
+                &ctx.accounts.token_state,
+                &ctx.accounts.whitelist,
+                &ctx.accounts.trader.key(),
+                amount,
+                now,
+                0,
+            )?;
+            Ok((net, tax))
+        }
+        TaxBackend::TransferFee => Ok((amount, 0)),
+    }
+}
+
+/// Checkpoint the trader's balance before the trade moves it
+fn record_trader_checkpoint(ctx: &Context<CurveTrade>) -> Result<()> {
+    let trader = ctx.accounts.trader.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    record_checkpoint(
+        &ctx.accounts.token_state,
+        ctx.accounts.trader_checkpoints.as_deref(),
+        &ctx.accounts.trader_token_account,
+        Some(&trader),
+        Some(&system_program),
+    )
+}
+
+/// Point a hooked mint's transfer hook at `program_id` around program-driven transfers
+fn toggle_hook(ctx: &Context<CurveTrade>, program_id: Option<Pubkey>) -> Result<()> {
+    if !ctx.accounts.token_state.transfer_hook_enabled {
+        return Ok(());
+    }
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.token_state.mint_authority_bump],
+    ]];
+    set_transfer_hook_program(
+        &ctx.accounts.mint,
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        program_id,
+    )
+}
+
+/// Move the token reserve to `lp_pool` and pay the SOL reserve out to the admin
+/// once the market cap threshold is reached or the curve sells out
+fn migrate_if_ready(
+    accounts: &mut CurveTrade<'_>,
+    curve_seeds: &[&[&[u8]]],
+    now: i64,
+) -> Result<()> {
+    let curve = &accounts.bonding_curve;
+    let market_cap = tokenomics::curve_market_cap(
+        &curve.shape,
+        curve.tokens_sold,
+        accounts.token_state.total_supply,
+    )
+    .ok_or(ErrorCode::ArithmeticOverflow)?;
+    if market_cap < curve.migration_market_cap && curve.tokens_sold < curve.curve_supply {
+        return Ok(());
+    }
+
+    let tokens = curve.curve_supply - curve.tokens_sold;
+    let lamports = curve.sol_reserve;
+
+    if tokens > 0 {
+        transfer_within_program(
+            &accounts.curve_reserve.to_account_info(),
+            &accounts.lp_pool.to_account_info(),
+            &accounts.bonding_curve.to_account_info(),
+            &accounts.mint,
+            &accounts.token_program,
+            curve_seeds,
+            tokens,
+        )?;
+    }
+
+    let curve = &mut accounts.bonding_curve;
+    curve.sub_lamports(lamports)?;
+    accounts.migration_payout.add_lamports(lamports)?;
+    curve.sol_reserve = 0;
+    curve.migrated = true;
+
+    let lp_balance = accounts
+        .lp_pool
+        .amount
+        .checked_add(tokens)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    accounts.token_state.update_liquidity_pool(lp_balance)?;
+
+    emit!(CurveMigratedEvent {
+        mint: accounts.mint.key(),
+        tokens,
+        payout: accounts.migration_payout.key(),
+        payout_lamports: lamports,
+        market_cap,
+        timestamp: now,
+    });
+
+    Ok(())
+}
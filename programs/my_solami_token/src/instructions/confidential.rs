@@ -0,0 +1,393 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::system_program::{self, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{confidential_transfer, ExtensionType},
+    proof::ProofLocation,
+    solana_zk_token_sdk::zk_token_elgamal::pod::{AeCiphertext, ElGamalPubkey},
+    state::Mint as SplMint,
+};
+use anchor_spl::token_interface::{
+    self, InitializeMint2, Mint, TokenAccount, TokenInterface, TransferHookInitialize,
+};
+
+use super::shared::{close_emptied_holder_record, record_checkpoint, track_holder_balance};
+use crate::errors::ErrorCode;
+use crate::events::ConfidentialBalanceEvent;
+use crate::state::{ConfidentialConfig, TokenState, Whitelist};
+
+#[derive(Accounts)]
+pub struct InitializeConfidentialMint<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Fresh keypair, created and initialized as a Token-2022 mint here
+    #[account(mut, signer)]
+    pub mint: AccountInfo<'info>,
+    /// CHECK: PDA that becomes mint, confidential transfer and hook authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ConfidentialConfig::SIZE,
+        seeds = [b"confidential_config", mint.key().as_ref()],
+        bump,
+    )]
+    pub confidential_config: Account<'info, ConfidentialConfig>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfidentialAdmin<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump = token_state.whitelist_bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    #[account(
+        mut,
+        seeds = [b"confidential_config", mint.key().as_ref()],
+        bump = confidential_config.bump,
+    )]
+    pub confidential_config: Account<'info, ConfidentialConfig>,
+    /// CHECK: PDA holding the confidential transfer authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// Account being approved, unused by `update_confidential_config`
+    #[account(mut, token::mint = mint)]
+    pub token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ConfidentialBalance<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        seeds = [b"confidential_config", mint.key().as_ref()],
+        bump = confidential_config.bump,
+    )]
+    pub confidential_config: Account<'info, ConfidentialConfig>,
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: Context state account holding a verified withdraw proof, only used on withdraw
+    pub proof_context: Option<AccountInfo<'info>>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of `token_account`, created with `payer`
+    /// if missing
+    #[account(mut)]
+    pub checkpoints: Option<UncheckedAccount<'info>>,
+    /// Pays for new checkpoints
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+    /// CHECK: `HolderRecord` PDA of `token_account`, required while loyalty tiers
+    /// are set and when a deposit empties the public balance
+    #[account(mut)]
+    pub holder_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: `rent_payer` of `holder_record`, refunded when the balance is emptied
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+}
+
+pub(crate) fn initialize_confidential_mint(
+    ctx: Context<InitializeConfidentialMint>,
+    decimals: u8,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+) -> Result<()> {
+    let mint_authority = ctx.accounts.mint_authority.key();
+    let token_program_id = ctx.accounts.token_program.key();
+
+    // Confidential mints also carry the transfer hook so the tax rules hold
+    let space = ExtensionType::try_calculate_account_len::<SplMint>(&[
+        ExtensionType::ConfidentialTransferMint,
+        ExtensionType::TransferHook,
+    ])?;
+    system_program::create_account(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            CreateAccount {
+                from: ctx.accounts.payer.to_account_info(),
+                to: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &token_program_id,
+    )?;
+
+    // Deposits and confidential transfers skip the tax, so accounts are never
+    // auto-approved: only `approve_confidential_account` can enable them.
+    invoke(
+        &confidential_transfer::instruction::initialize_mint(
+            &token_program_id,
+            &ctx.accounts.mint.key(),
+            Some(mint_authority),
+            false,
+            auditor_elgamal_pubkey.map(ElGamalPubkey),
+        )?,
+        &[ctx.accounts.mint.to_account_info()],
+    )?;
+
+    token_interface::transfer_hook_initialize(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferHookInitialize {
+                token_program_id: ctx.accounts.token_program.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        Some(mint_authority),
+        Some(crate::ID),
+    )?;
+
+    token_interface::initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: ctx.accounts.mint.to_account_info(),
+            },
+        ),
+        decimals,
+        &mint_authority,
+        None,
+    )?;
+
+    let config = &mut ctx.accounts.confidential_config;
+    config.mint = ctx.accounts.mint.key();
+    config.auditor_elgamal_pubkey = auditor_elgamal_pubkey.unwrap_or([0; 32]);
+    config.bump = ctx.bumps.confidential_config;
+
+    Ok(())
+}
+
+pub(crate) fn update_confidential_config(
+    ctx: Context<ConfidentialAdmin>,
+    auditor_elgamal_pubkey: Option<[u8; 32]>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    invoke_signed(
+        &confidential_transfer::instruction::update_mint(
+            &ctx.accounts.token_program.key(),
+            &mint_key,
+            &ctx.accounts.mint_authority.key(),
+            &[],
+            false,
+            auditor_elgamal_pubkey.map(ElGamalPubkey),
+        )?,
+        &[
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.mint_authority.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    let config = &mut ctx.accounts.confidential_config;
+    config.auditor_elgamal_pubkey = auditor_elgamal_pubkey.unwrap_or([0; 32]);
+
+    Ok(())
+}
+
+pub(crate) fn approve_confidential_account(ctx: Context<ConfidentialAdmin>) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    let token_account = ctx.accounts.token_account
+        .as_ref()
+        .ok_or(ErrorCode::AccountNotFound)?;
+
+    // An approved account can deposit and transfer confidentially straight
+    // through Token-2022, so only untaxed (whitelisted) owners are approved.
+    require!(
+        ctx.accounts.whitelist.contains(&token_account.owner),
+        ErrorCode::ConfidentialNotWhitelisted
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    invoke_signed(
+        &confidential_transfer::instruction::approve_account(
+            &ctx.accounts.token_program.key(),
+            &token_account.key(),
+            &mint_key,
+            &ctx.accounts.mint_authority.key(),
+            &[],
+        )?,
+        &[
+            token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.mint_authority.clone(),
+        ],
+        signer_seeds,
+    )?;
+
+    Ok(())
+}
+
+pub(crate) fn confidential_deposit(
+    ctx: Context<ConfidentialBalance>,
+    amount: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(!ctx.accounts.token_state.is_frozen, ErrorCode::ContractFrozen);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.token_account.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+    record_balance_checkpoint(&ctx)?;
+
+    invoke(
+        &confidential_transfer::instruction::deposit(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.token_account.key(),
+            &ctx.accounts.mint.key(),
+            amount,
+            ctx.accounts.mint.decimals,
+            &ctx.accounts.owner.key(),
+            &[],
+        )?,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            ctx.accounts.owner.to_account_info(),
+        ],
+    )?;
+
+    track_holder_balance(
+        &ctx.accounts.token_state,
+        ctx.accounts.holder_record.as_deref(),
+        &mut ctx.accounts.token_account,
+        now,
+    )?;
+    close_emptied_holder_record(
+        &mut ctx.accounts.token_state,
+        ctx.accounts.holder_record.as_deref(),
+        ctx.accounts.rent_recipient.as_deref(),
+        &ctx.accounts.token_account,
+        now,
+    )?;
+
+    emit!(ConfidentialBalanceEvent {
+        owner: ctx.accounts.owner.key(),
+        token_account: ctx.accounts.token_account.key(),
+        amount,
+        tax_amount: 0,
+        is_deposit: true,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn confidential_withdraw(
+    ctx: Context<ConfidentialBalance>,
+    amount: u64,
+    new_decryptable_available_balance: [u8; 36],
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(!ctx.accounts.token_state.is_frozen, ErrorCode::ContractFrozen);
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let proof_context = ctx.accounts.proof_context
+        .as_ref()
+        .ok_or(ErrorCode::MissingProofContext)?;
+    record_balance_checkpoint(&ctx)?;
+
+    invoke(
+        &confidential_transfer::instruction::inner_withdraw(
+            &ctx.accounts.token_program.key(),
+            &ctx.accounts.token_account.key(),
+            &ctx.accounts.mint.key(),
+            amount,
+            ctx.accounts.mint.decimals,
+            AeCiphertext(new_decryptable_available_balance),
+            &ctx.accounts.owner.key(),
+            &[],
+            ProofLocation::ContextStateAccount(&proof_context.key()),
+        )?,
+        &[
+            ctx.accounts.token_account.to_account_info(),
+            ctx.accounts.mint.to_account_info(),
+            proof_context.clone(),
+            ctx.accounts.owner.to_account_info(),
+        ],
+    )?;
+
+    track_holder_balance(
+        &ctx.accounts.token_state,
+        ctx.accounts.holder_record.as_deref(),
+        &mut ctx.accounts.token_account,
+        now,
+    )?;
+
+    emit!(ConfidentialBalanceEvent {
+        owner: ctx.accounts.owner.key(),
+        token_account: ctx.accounts.token_account.key(),
+        amount,
+        tax_amount: 0,
+        is_deposit: false,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Checkpoint the public balance before a deposit or withdraw moves it
+fn record_balance_checkpoint(ctx: &Context<ConfidentialBalance>) -> Result<()> {
+    record_checkpoint(
+        &ctx.accounts.token_state,
+        ctx.accounts.checkpoints.as_deref(),
+        &ctx.accounts.token_account,
+        ctx.accounts.payer.as_deref(),
+        ctx.accounts.system_program.as_deref(),
+    )
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct ConfigureEmission<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// Emissions only fund the rewards or staking pool
+    #[account(
+        token::mint = mint,
+        constraint = emission_recipient.key() == token_state.rewards_pool
+            || emission_recipient.key() == token_state.staking_pool
+            @ ErrorCode::InvalidEmissionConfig,
+    )]
+    pub emission_recipient: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ConfigureEmission>,
+    max_supply: u64,
+    emission_per_epoch: u64,
+    epoch_duration: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    require!(
+        !ctx.accounts.token_state.supply_finalized,
+        ErrorCode::SupplyFinalized
+    );
+
+    require!(
+        max_supply >= ctx.accounts.mint.supply,
+        ErrorCode::InvalidEmissionConfig
+    );
+
+    require!(
+        emission_per_epoch == 0 || epoch_duration > 0,
+        ErrorCode::InvalidEmissionConfig
+    );
+
+    let token_state = &mut ctx.accounts.token_state;
+    token_state.max_supply = max_supply;
+    token_state.emission_per_epoch = emission_per_epoch;
+    token_state.emission_epoch_duration = epoch_duration;
+    token_state.last_emission_time = Clock::get()?.unix_timestamp;
+    token_state.emission_recipient = ctx.accounts.emission_recipient.key();
+
+    Ok(())
+}
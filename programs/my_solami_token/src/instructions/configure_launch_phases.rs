@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::ErrorCode;
+use crate::events::LaunchPhaseChangedEvent;
+use crate::state::{LaunchPhase, TokenState};
+
+#[derive(Accounts)]
+pub struct ConfigureLaunchPhases<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ConfigureLaunchPhases>,
+    whitelist_phase_start: i64,
+    limited_phase_start: i64,
+    public_phase_start: i64,
+    limited_max_transfer: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let token_state = &mut ctx.accounts.token_state;
+
+    require!(
+        token_state.launch_phase_at(now) != LaunchPhase::Public,
+        ErrorCode::LaunchComplete
+    );
+
+    require!(
+        whitelist_phase_start <= limited_phase_start
+            && limited_phase_start <= public_phase_start,
+        ErrorCode::InvalidLaunchSchedule
+    );
+
+    require!(limited_max_transfer > 0, ErrorCode::InvalidLaunchSchedule);
+
+    token_state.whitelist_phase_start = whitelist_phase_start;
+    token_state.limited_phase_start = limited_phase_start;
+    token_state.public_phase_start = public_phase_start;
+    token_state.limited_max_transfer = limited_max_transfer;
+
+    if let Some(previous_phase) = token_state.advance_launch_phase(now) {
+        emit!(LaunchPhaseChangedEvent {
+            mint: ctx.accounts.mint.key(),
+            previous_phase,
+            phase: token_state.launch_phase,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
+}
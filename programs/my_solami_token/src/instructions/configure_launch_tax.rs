@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::ErrorCode;
+use crate::state::{LaunchPhase, TaxBackend, TokenState};
+use crate::tokenomics::BASE_TAX_BPS;
+
+#[derive(Accounts)]
+pub struct ConfigureLaunchTax<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ConfigureLaunchTax>,
+    start_bps: u16,
+    window: i64,
+    steps: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let token_state = &mut ctx.accounts.token_state;
+
+    require!(
+        token_state.launch_phase_at(now) == LaunchPhase::PreLaunch,
+        ErrorCode::LaunchTaxLocked
+    );
+
+    set_launch_tax(token_state, start_bps, window, steps)
+}
+
+/// Validate and store a launch tax curve; the caller decides when it may change
+pub(crate) fn set_launch_tax(
+    token_state: &mut TokenState,
+    start_bps: u16,
+    window: i64,
+    steps: u8,
+) -> Result<()> {
+    // The curve is only applied by the program-side tax path
+    require!(
+        token_state.tax_backend == TaxBackend::Program,
+        ErrorCode::InvalidTaxBackend
+    );
+
+    require!(
+        (BASE_TAX_BPS..=10_000).contains(&start_bps) && window >= 0,
+        ErrorCode::InvalidLaunchTax
+    );
+
+    token_state.launch_tax_bps = start_bps;
+    token_state.launch_tax_window = window;
+    token_state.launch_tax_steps = steps;
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::ErrorCode;
+use crate::state::{LoyaltyTier, LoyaltyTiers, TaxBackend, TokenState, MAX_LOYALTY_TIERS};
+
+#[derive(Accounts)]
+pub struct ConfigureLoyaltyTiers<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + LoyaltyTiers::SIZE,
+        seeds = [b"loyalty_tiers", mint.key().as_ref()],
+        bump,
+    )]
+    pub loyalty_tiers: Account<'info, LoyaltyTiers>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<ConfigureLoyaltyTiers>, tiers: Vec<LoyaltyTier>) -> Result<()> {
+    let token_state = &mut ctx.accounts.token_state;
+
+    // Discounts are only applied by the program-side tax path
+    require!(
+        token_state.tax_backend == TaxBackend::Program,
+        ErrorCode::InvalidTaxBackend
+    );
+
+    require!(
+        tiers.len() <= MAX_LOYALTY_TIERS,
+        ErrorCode::InvalidLoyaltyTiers
+    );
+    require!(
+        tiers
+            .iter()
+            .all(|tier| tier.min_hold >= 0 && tier.min_balance > 0 && tier.discount_bps <= 10_000),
+        ErrorCode::InvalidLoyaltyTiers
+    );
+
+    // An empty tier list turns hold tracking and discounts off
+    token_state.loyalty_min_balance = tiers.iter().map(|tier| tier.min_balance).min().unwrap_or(0);
+    // Time held before these tiers were set earns no discount under them
+    token_state.loyalty_since = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.loyalty_tiers.tiers = tiers;
+    ctx.accounts.loyalty_tiers.bump = ctx.bumps.loyalty_tiers;
+
+    Ok(())
+}
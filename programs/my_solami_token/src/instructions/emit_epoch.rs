@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::mint_tokens;
+use crate::errors::ErrorCode;
+use crate::events::EmissionEvent;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct EmitEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: PDA holding mint authority, used to sign the emission
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut, address = token_state.emission_recipient)]
+    pub emission_recipient: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(ctx: Context<EmitEpoch>) -> Result<()> {
+    let token_state = &ctx.accounts.token_state;
+
+    require!(
+        token_state.emission_per_epoch > 0 && token_state.emission_epoch_duration > 0,
+        ErrorCode::EmissionNotConfigured
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let epoch_time = token_state.last_emission_time
+        .checked_add(token_state.emission_epoch_duration)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    require!(now >= epoch_time, ErrorCode::EmissionEpochNotElapsed);
+
+    let remaining = token_state.max_supply.saturating_sub(ctx.accounts.mint.supply);
+    require!(remaining > 0, ErrorCode::MaxSupplyReached);
+    let amount = token_state.emission_per_epoch.min(remaining);
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    mint_tokens(
+        token_state,
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.emission_recipient.to_account_info(),
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        amount,
+    )?;
+
+    // Advance by a single epoch so missed epochs can be cranked one at a time
+    let token_state = &mut ctx.accounts.token_state;
+    token_state.last_emission_time = epoch_time;
+    token_state.total_emitted = token_state.total_emitted
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    token_state.total_supply = token_state.total_supply
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit!(EmissionEvent {
+        recipient: ctx.accounts.emission_recipient.key(),
+        amount,
+        epoch_time,
+        total_emitted: token_state.total_emitted,
+        new_total_supply: token_state.total_supply,
+    });
+
+    Ok(())
+}
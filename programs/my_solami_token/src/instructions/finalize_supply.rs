@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+
+use super::shared::revoke_mint_authority;
+use crate::errors::ErrorCode;
+use crate::events::SupplyFinalizedEvent;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct FinalizeSupply<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    /// CHECK: PDA currently holding mint (and optionally freeze) authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<FinalizeSupply>,
+    revoke_freeze_authority: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    require!(
+        !ctx.accounts.token_state.supply_finalized,
+        ErrorCode::SupplyFinalized
+    );
+
+    let mint_authority = ctx.accounts.mint_authority.key();
+    require!(
+        ctx.accounts.mint.mint_authority == COption::Some(mint_authority),
+        ErrorCode::AccountMismatch
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    revoke_mint_authority(
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        AuthorityType::MintTokens,
+    )?;
+
+    let freeze_authority_revoked = if revoke_freeze_authority {
+        match ctx.accounts.mint.freeze_authority {
+            COption::Some(authority) if authority == mint_authority => {
+                revoke_mint_authority(
+                    &ctx.accounts.mint.to_account_info(),
+                    &ctx.accounts.mint_authority,
+                    &ctx.accounts.token_program,
+                    signer_seeds,
+                    AuthorityType::FreezeAccount,
+                )?;
+                true
+            }
+            COption::None => true,
+            _ => return err!(ErrorCode::Unauthorized),
+        }
+    } else {
+        false
+    };
+
+    ctx.accounts.token_state.supply_finalized = true;
+
+    emit!(SupplyFinalizedEvent {
+        mint: mint_key,
+        final_supply: ctx.accounts.mint.supply,
+        freeze_authority_revoked,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::ErrorCode;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct FreezeContract<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<FreezeContract>,
+    freeze: bool,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    ctx.accounts.token_state.is_frozen = freeze;
+    Ok(())
+}
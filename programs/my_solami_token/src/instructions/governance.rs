@@ -0,0 +1,498 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        confidential_transfer::ConfidentialTransferAccount, BaseStateWithExtensions,
+        StateWithExtensions,
+    },
+    state::Account as SplTokenAccount,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use super::configure_launch_tax::set_launch_tax;
+use super::shared::{open_snapshot, snapshot_balance};
+use crate::errors::ErrorCode;
+use crate::events::{ProposalCreatedEvent, ProposalExecutedEvent, VoteCastEvent};
+use crate::program::MySolamiToken;
+use crate::state::{
+    BalanceCheckpoints, Governance, Proposal, ProposalAction, Snapshot, TokenState, VoteRecord,
+};
+
+#[derive(Accounts)]
+pub struct ConfigureGovernance<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + Governance::SIZE,
+        seeds = [b"governance", mint.key().as_ref()],
+        bump,
+    )]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        mut,
+        seeds = [b"governance", mint.key().as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::SIZE,
+        seeds = [
+            b"proposal",
+            mint.key().as_ref(),
+            &governance.proposal_count.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// Taken for the proposal; votes are weighed by balances at it
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Snapshot::SIZE,
+        seeds = [
+            b"snapshot",
+            mint.key().as_ref(),
+            &(token_state.snapshot_id + 1).to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub snapshot: Account<'info, Snapshot>,
+    #[account(
+        token::mint = mint,
+        token::authority = proposer,
+    )]
+    pub proposer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        mut,
+        seeds = [b"proposal", mint.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(
+        token::mint = mint,
+        token::authority = owner,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: `BalanceCheckpoints` of `voter_token_account`, which must have been
+    /// open before the proposal's snapshot for its balance there to count
+    #[account(
+        seeds = [b"checkpoints", voter_token_account.key().as_ref()],
+        bump,
+    )]
+    pub checkpoints: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + VoteRecord::SIZE,
+        seeds = [b"vote", proposal.key().as_ref(), voter_token_account.key().as_ref()],
+        bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// Signs the executed instruction as `TokenState::admin`
+    #[account(
+        mut,
+        seeds = [b"governance", mint.key().as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+    #[account(
+        mut,
+        seeds = [b"proposal", mint.key().as_ref(), &proposal.id.to_le_bytes()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Written by the executed instruction, which validates it; an
+    /// `Account` here would write its stale copy back on exit
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_state: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub this_program: Program<'info, MySolamiToken>,
+}
+
+/// A launch tax change voted through governance; unlike the admin's
+/// `configure_launch_tax` it isn't locked once trading opens
+#[derive(Accounts)]
+pub struct GovernLaunchTax<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        address = token_state.admin,
+        seeds = [b"governance", mint.key().as_ref()],
+        bump,
+    )]
+    pub governance: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn configure_governance(
+    ctx: Context<ConfigureGovernance>,
+    voting_period: i64,
+    quorum: u64,
+    proposal_threshold: u64,
+) -> Result<()> {
+    require!(
+        voting_period > 0 && quorum > 0,
+        ErrorCode::InvalidGovernanceConfig
+    );
+    // Without the hook direct transfers go unrecorded and snapshots prove nothing
+    require!(
+        ctx.accounts.token_state.transfer_hook_enabled,
+        ErrorCode::GovernanceNeedsTransferHook
+    );
+
+    let governance = &mut ctx.accounts.governance;
+    governance.mint = ctx.accounts.mint.key();
+    governance.voting_period = voting_period;
+    governance.quorum = quorum;
+    governance.proposal_threshold = proposal_threshold;
+    governance.bump = ctx.bumps.governance;
+
+    Ok(())
+}
+
+pub(crate) fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    require!(
+        ctx.accounts.token_state.transfer_hook_enabled,
+        ErrorCode::GovernanceNeedsTransferHook
+    );
+
+    // The snapshot is taken now, so the proposer's balance is its balance at it
+    let power = ctx.accounts.proposer_token_account.amount;
+    let governance = &mut ctx.accounts.governance;
+    require!(
+        power > 0 && power >= governance.proposal_threshold,
+        ErrorCode::InsufficientVotingPower
+    );
+
+    let snapshot_id = open_snapshot(
+        &mut ctx.accounts.token_state,
+        &mut ctx.accounts.snapshot,
+        ctx.accounts.mint.key(),
+        ctx.bumps.snapshot,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let voting_ends = now
+        .checked_add(governance.voting_period)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let id = governance.proposal_count;
+
+    ctx.accounts.proposal.set_inner(Proposal {
+        mint: governance.mint,
+        id,
+        proposer: ctx.accounts.proposer.key(),
+        action,
+        snapshot_id,
+        voting_ends,
+        yes_votes: 0,
+        no_votes: 0,
+        executed: false,
+        bump: ctx.bumps.proposal,
+    });
+    governance.proposal_count = id.checked_add(1).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit!(ProposalCreatedEvent {
+        mint: governance.mint,
+        proposal_id: id,
+        proposer: ctx.accounts.proposer.key(),
+        action,
+        snapshot_id,
+        voting_ends,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn cast_vote(ctx: Context<CastVote>, approve: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(now < proposal.voting_ends, ErrorCode::VotingClosed);
+    check_provable(
+        &ctx.accounts.token_state,
+        &ctx.accounts.checkpoints,
+        &ctx.accounts.voter_token_account,
+        proposal.snapshot_id,
+    )?;
+
+    // Tokens moved after the snapshot keep counting for the account that held them
+    let weight = snapshot_balance(
+        &ctx.accounts.token_state,
+        &ctx.accounts.checkpoints,
+        &ctx.accounts.voter_token_account,
+        proposal.snapshot_id,
+    )?;
+    require!(weight > 0, ErrorCode::InsufficientVotingPower);
+
+    let tally = if approve {
+        &mut proposal.yes_votes
+    } else {
+        &mut proposal.no_votes
+    };
+    *tally = tally
+        .checked_add(weight)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    let voter = ctx.accounts.owner.key();
+    ctx.accounts.vote_record.set_inner(VoteRecord {
+        proposal: proposal.key(),
+        voter,
+        token_account: ctx.accounts.voter_token_account.key(),
+        approve,
+        weight,
+        bump: ctx.bumps.vote_record,
+    });
+
+    emit!(VoteCastEvent {
+        mint: proposal.mint,
+        proposal_id: proposal.id,
+        voter,
+        approve,
+        weight,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn execute_proposal<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteProposal<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let governance = &ctx.accounts.governance;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed, ErrorCode::ProposalExecuted);
+    require!(
+        proposal.passed(governance.quorum, now),
+        ErrorCode::ProposalNotPassed
+    );
+
+    let token_state = {
+        let data = ctx.accounts.token_state.try_borrow_data()?;
+        TokenState::try_deserialize(&mut &data[..])?
+    };
+    require_keys_eq!(token_state.admin, governance.key(), ErrorCode::Unauthorized);
+
+    proposal.executed = true;
+
+    let mint_key = ctx.accounts.mint.key();
+    let ix = action_instruction(
+        &proposal.action,
+        &mint_key,
+        &governance.key(),
+        &ctx.accounts.token_state.key(),
+        &token_state,
+    )?;
+
+    // The executed instruction's own constraints check whatever the action
+    // needs beyond these, passed as remaining accounts
+    let mut account_infos = vec![
+        governance.to_account_info(),
+        ctx.accounts.token_state.to_account_info(),
+        ctx.accounts.mint.to_account_info(),
+        ctx.accounts.this_program.to_account_info(),
+    ];
+    account_infos.extend_from_slice(ctx.remaining_accounts);
+
+    invoke_signed(
+        &ix,
+        &account_infos,
+        &[&[b"governance", mint_key.as_ref(), &[governance.bump]]],
+    )?;
+
+    emit!(ProposalExecutedEvent {
+        mint: mint_key,
+        proposal_id: proposal.id,
+        action: proposal.action,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn govern_launch_tax(
+    ctx: Context<GovernLaunchTax>,
+    start_bps: u16,
+    window: i64,
+    steps: u8,
+) -> Result<()> {
+    set_launch_tax(&mut ctx.accounts.token_state, start_bps, window, steps)
+}
+
+/// Check every change to `token_account`'s balance since snapshot `snapshot_id`
+/// was checkpointed, so the balance read at it can't have been counted elsewhere
+fn check_provable(
+    token_state: &TokenState,
+    checkpoints: &AccountInfo,
+    token_account: &InterfaceAccount<TokenAccount>,
+    snapshot_id: u64,
+) -> Result<()> {
+    require!(
+        token_state.transfer_hook_enabled,
+        ErrorCode::GovernanceNeedsTransferHook
+    );
+
+    // Confidential withdrawals credit the public balance without the hook
+    {
+        let info = token_account.to_account_info();
+        let data = info.try_borrow_data()?;
+        let account = StateWithExtensions::<SplTokenAccount>::unpack(&data)?;
+        require!(
+            account.get_extension::<ConfidentialTransferAccount>().is_err(),
+            ErrorCode::BalanceNotProvable
+        );
+    }
+
+    // Changes before the account was opened, direct ones included, went unrecorded
+    require!(!checkpoints.data_is_empty(), ErrorCode::BalanceNotProvable);
+    let data = checkpoints.try_borrow_data()?;
+    let history = BalanceCheckpoints::try_deserialize(&mut &data[..])?;
+    require!(
+        history.opened_at < snapshot_id,
+        ErrorCode::BalanceNotProvable
+    );
+    Ok(())
+}
+
+/// The admin instruction `action` stands for, signed by `admin`
+fn action_instruction(
+    action: &ProposalAction,
+    mint: &Pubkey,
+    admin: &Pubkey,
+    token_state_key: &Pubkey,
+    token_state: &TokenState,
+) -> Result<Instruction> {
+    let (accounts, data) = match *action {
+        ProposalAction::ConfigureLaunchTax {
+            start_bps,
+            window,
+            steps,
+        } => (
+            crate::accounts::GovernLaunchTax {
+                token_state: *token_state_key,
+                governance: *admin,
+                mint: *mint,
+            }
+            .to_account_metas(None),
+            crate::instruction::GovernLaunchTax {
+                start_bps,
+                window,
+                steps,
+            }
+            .data(),
+        ),
+        ProposalAction::ConfigureReferrals { referral_bps } => (
+            crate::accounts::ConfigureReferrals {
+                token_state: *token_state_key,
+                referral_vault: token_state.referral_vault,
+                mint_authority: program_address(
+                    &[b"mint_authority", mint.as_ref()],
+                    token_state.mint_authority_bump,
+                )?,
+                admin: *admin,
+                mint: *mint,
+            }
+            .to_account_metas(None),
+            crate::instruction::ConfigureReferrals { referral_bps }.data(),
+        ),
+        ProposalAction::AddToWhitelist { wallet } => (
+            whitelist_metas(mint, admin, token_state_key, token_state)?,
+            crate::instruction::AddToWhitelist { wallet }.data(),
+        ),
+        ProposalAction::RemoveFromWhitelist { wallet } => (
+            whitelist_metas(mint, admin, token_state_key, token_state)?,
+            crate::instruction::RemoveFromWhitelist { wallet }.data(),
+        ),
+        ProposalAction::FreezeContract { freeze } => (
+            crate::accounts::FreezeContract {
+                token_state: *token_state_key,
+                admin: *admin,
+                mint: *mint,
+            }
+            .to_account_metas(None),
+            crate::instruction::FreezeContract { freeze }.data(),
+        ),
+    };
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data,
+    })
+}
+
+fn whitelist_metas(
+    mint: &Pubkey,
+    admin: &Pubkey,
+    token_state_key: &Pubkey,
+    token_state: &TokenState,
+) -> Result<Vec<AccountMeta>> {
+    Ok(crate::accounts::WhitelistOperation {
+        token_state: *token_state_key,
+        admin: *admin,
+        whitelist: program_address(&[b"whitelist", mint.as_ref()], token_state.whitelist_bump)?,
+        mint: *mint,
+    }
+    .to_account_metas(None))
+}
+
+/// Program address for `seeds` with a stored bump
+fn program_address(seeds: &[&[u8]], bump: u8) -> Result<Pubkey> {
+    let bump = [bump];
+    let mut seeds = seeds.to_vec();
+    seeds.push(&bump);
+    Pubkey::create_program_address(&seeds, &crate::ID)
+        .map_err(|_| error!(ErrorCode::AccountMismatch))
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, HarvestWithheldTokensToMint, Mint, TokenAccount, TokenInterface,
+    WithdrawWithheldTokensFromMint,
+};
+
+use super::shared::{burn_tokens, set_transfer_hook_program, transfer_within_program};
+use crate::errors::ErrorCode;
+use crate::events::FeesHarvestedEvent;
+use crate::state::{TaxBackend, TokenState};
+use crate::tokenomics;
+
+#[derive(Accounts)]
+pub struct HarvestFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = rewards_pool,
+        has_one = lp_fund,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: PDA holding the withdraw-withheld authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = mint_authority,
+    )]
+    pub fee_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_fund: InterfaceAccount<'info, TokenAccount>,
+    #[account(address = anchor_spl::token_2022::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub(crate) fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, HarvestFees<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.token_state.tax_backend == TaxBackend::TransferFee,
+        ErrorCode::InvalidTaxBackend
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    // Only what this call withdraws is split, not tokens already in the vault
+    let vault_before = ctx.accounts.fee_vault.amount;
+
+    // Sweep fees withheld on the passed token accounts into the mint
+    if !ctx.remaining_accounts.is_empty() {
+        token_interface::harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            ctx.remaining_accounts.to_vec(),
+        )?;
+    }
+
+    token_interface::withdraw_withheld_tokens_from_mint(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        WithdrawWithheldTokensFromMint {
+            token_program_id: ctx.accounts.token_program.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            destination: ctx.accounts.fee_vault.to_account_info(),
+            authority: ctx.accounts.mint_authority.clone(),
+        },
+        signer_seeds,
+    ))?;
+
+    ctx.accounts.fee_vault.reload()?;
+    let fee_amount = ctx.accounts.fee_vault.amount
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    require!(fee_amount > 0, ErrorCode::InvalidAmount);
+
+    let tokenomics::TaxSplit { rewards, lp_fund, burn: burn_amount, .. } =
+        tokenomics::split_tax(fee_amount);
+    let hook_enabled = ctx.accounts.token_state.transfer_hook_enabled;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            None,
+        )?;
+    }
+
+    // Fees withheld on these transfers are picked up by the next harvest
+    transfer_within_program(
+        &ctx.accounts.fee_vault.to_account_info(),
+        &ctx.accounts.rewards_pool.to_account_info(),
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        rewards,
+    )?;
+
+    transfer_within_program(
+        &ctx.accounts.fee_vault.to_account_info(),
+        &ctx.accounts.lp_fund.to_account_info(),
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        lp_fund,
+    )?;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            Some(crate::ID),
+        )?;
+    }
+
+    burn_tokens(
+        &ctx.accounts.mint,
+        &ctx.accounts.fee_vault,
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        burn_amount,
+    )?;
+
+    ctx.accounts.token_state.record_tax(fee_amount, burn_amount)?;
+
+    emit!(FeesHarvestedEvent {
+        amount: fee_amount,
+        rewards,
+        lp_fund,
+        burned: burn_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
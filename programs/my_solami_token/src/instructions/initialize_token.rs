@@ -0,0 +1,358 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token_interface::{
+    self, Mint, MintTo, TokenAccount, TokenInterface, TransferFeeSetTransferFee,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+
+use crate::errors::ErrorCode;
+use crate::state::{TaxBackend, TokenState, TransferFeeParams, Whitelist, MAX_WHITELIST_WALLETS};
+use crate::tokenomics;
+
+#[derive(Accounts)]
+pub struct InitializeToken<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + TokenState::SIZE,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Whitelist::SIZE,
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    /// CHECK: This is the PDA that will be the mint authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+
+    #[account(mut, mint::authority = mint_authority)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+    )]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_fund: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub staking_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub swap_wallet: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Owned by the `presale` PDA, required when `presale_tranche` is non-zero
+    #[account(mut, token::mint = mint)]
+    pub presale_escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Owned by the `bonding_curve` PDA; when set the LP share seeds the curve instead of `lp_pool`
+    #[account(mut, token::mint = mint)]
+    pub curve_reserve: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub(crate) fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitializeToken<'info>>,
+    total_supply: u64,
+    whitelist_wallets: Vec<Pubkey>,
+    transfer_fee: Option<TransferFeeParams>,
+    presale_tranche: u64,
+) -> Result<()> {
+    require!(total_supply > 0, ErrorCode::InvalidSupply);
+
+    require!(
+        !whitelist_wallets.is_empty() && whitelist_wallets.len() <= MAX_WHITELIST_WALLETS,
+        ErrorCode::InvalidWhitelistSize
+    );
+
+    let (mint_authority, _mint_bump) = Pubkey::find_program_address(
+        &[b"mint_authority", ctx.accounts.mint.key().as_ref()],
+        ctx.program_id
+    );
+
+    require!(
+        ctx.accounts.mint_authority.key() == mint_authority,
+        ErrorCode::AccountMismatch
+    );
+
+    ctx.accounts.token_state.initialize(
+        ctx.accounts.admin.key(),
+        total_supply,
+        Clock::get()?.unix_timestamp,
+    );
+    ctx.accounts.token_state.lp_pool = ctx.accounts.lp_pool.key();
+    ctx.accounts.token_state.rewards_pool = ctx.accounts.rewards_pool.key();
+    ctx.accounts.token_state.lp_fund = ctx.accounts.lp_fund.key();
+    ctx.accounts.token_state.staking_pool = ctx.accounts.staking_pool.key();
+    ctx.accounts.token_state.swap_wallet = ctx.accounts.swap_wallet.key();
+    ctx.accounts.token_state.bump = ctx.bumps.token_state;
+    ctx.accounts.token_state.whitelist_bump = ctx.bumps.whitelist;
+    ctx.accounts.token_state.mint_authority_bump = ctx.bumps.mint_authority;
+    ctx.accounts.whitelist.initialize(whitelist_wallets.clone());
+    // Rounding dust is never minted, so record what the mint will actually hold
+    ctx.accounts.token_state.total_supply =
+        tokenomics::initial_allocation(total_supply, whitelist_wallets.len())?
+            .with_presale(presale_tranche)?
+            .minted(whitelist_wallets.len() as u64);
+
+    if presale_tranche > 0 {
+        let escrow = ctx
+            .accounts
+            .presale_escrow
+            .as_ref()
+            .ok_or(ErrorCode::InvalidPresale)?;
+        let (presale, _) = Pubkey::find_program_address(
+            &[b"presale", ctx.accounts.mint.key().as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(escrow.owner, presale, ErrorCode::InvalidPresale);
+
+        ctx.accounts.token_state.presale_escrow = escrow.key();
+        ctx.accounts.token_state.presale_tranche = presale_tranche;
+    }
+
+    if let Some(curve_reserve) = &ctx.accounts.curve_reserve {
+        let (bonding_curve, _) = Pubkey::find_program_address(
+            &[b"bonding_curve", ctx.accounts.mint.key().as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            curve_reserve.owner,
+            bonding_curve,
+            ErrorCode::InvalidBondingCurve
+        );
+
+        ctx.accounts.token_state.curve_reserve = curve_reserve.key();
+    }
+
+    if let Some(fee) = transfer_fee {
+        require!(
+            ctx.accounts.token_program.key() == anchor_spl::token_2022::ID,
+            ErrorCode::InvalidTaxBackend
+        );
+        require!(fee.basis_points <= 10_000, ErrorCode::InvalidTransferFee);
+
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"mint_authority",
+            mint_key.as_ref(),
+            &[ctx.bumps.mint_authority],
+        ]];
+
+        // The mint's transfer fee config authority must be the mint_authority PDA
+        token_interface::transfer_fee_set(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferFeeSetTransferFee {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    authority: ctx.accounts.mint_authority.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fee.basis_points,
+            fee.maximum_fee,
+        )?;
+
+        ctx.accounts.token_state.tax_backend = TaxBackend::TransferFee;
+    }
+
+    distribute_initial_supply(
+        ctx,
+        total_supply,
+        &whitelist_wallets,
+        presale_tranche,
+    )?;
+
+    Ok(())
+}
+
+/// Distribute initial supply according to tokenomics
+fn distribute_initial_supply<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, InitializeToken<'info>>,
+    total_supply: u64,
+    whitelist_wallets: &Vec<Pubkey>,
+    presale_tranche: u64,
+) -> Result<()> {
+    // Calculate allocations
+    let allocation = tokenomics::initial_allocation(total_supply, whitelist_wallets.len())?
+        .with_presale(presale_tranche)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+
+    // Mint to liquidity pool, or to the bonding curve reserve in fair launch mode
+    let lp_target = match &ctx.accounts.curve_reserve {
+        Some(curve_reserve) => curve_reserve.to_account_info(),
+        None => ctx.accounts.lp_pool.to_account_info(),
+    };
+    let mint_cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: lp_target,
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_cpi_accounts,
+            signer_seeds,
+        ),
+        allocation.lp,
+    )?;
+
+    // Mint to admin for burn allocation
+    let mint_cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.admin_token_account.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_cpi_accounts,
+            signer_seeds,
+        ),
+        allocation.burn_allocation,
+    )?;
+
+    // Mint to admin for marketing
+    let mint_cpi_accounts = MintTo {
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.admin_token_account.to_account_info(),
+        authority: ctx.accounts.mint_authority.to_account_info(),
+    };
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_cpi_accounts,
+            signer_seeds,
+        ),
+        allocation.marketing,
+    )?;
+
+    // Escrow the presale tranche until buyers claim it
+    if allocation.presale > 0 {
+        let presale_escrow = ctx
+            .accounts
+            .presale_escrow
+            .as_ref()
+            .ok_or(ErrorCode::InvalidPresale)?;
+        let mint_cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: presale_escrow.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                mint_cpi_accounts,
+                signer_seeds,
+            ),
+            allocation.presale,
+        )?;
+    }
+
+    // Convert remaining_accounts to an iterator
+    let mut accounts_iter = ctx.remaining_accounts.iter();
+
+    // Mint to each whitelisted wallet
+    for wallet in whitelist_wallets {
+        // Get or create the ATA
+        let recipient_token_account = next_account_info(&mut accounts_iter)?;
+        get_or_create_associated_token_account(
+            ctx.accounts.token_program.clone(),
+            ctx.accounts.system_program.clone(),
+            ctx.accounts.rent.clone(),
+            &ctx.accounts.mint,
+            *wallet,
+            &ctx.accounts.admin,
+            recipient_token_account,
+        )?;
+
+        let mint_cpi_accounts = MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: recipient_token_account.clone(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                mint_cpi_accounts,
+                signer_seeds,
+            ),
+            allocation.whitelist_per_wallet,
+        )?;
+    }
+    Ok(())
+}
+
+// Helper function to get or create associated token account
+fn get_or_create_associated_token_account<'info>(
+    token_program: Interface<'info, TokenInterface>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+    mint: &InterfaceAccount<'info, Mint>,
+    owner: Pubkey,
+    payer: &Signer<'info>,
+    associated_token_account: &AccountInfo<'info>,
+) -> Result<()> {
+    // Derive the associated token account address
+    let ata_address = get_associated_token_address_with_program_id(&owner, &mint.key(), &token_program.key());
+
+    // Check if we need to create the account
+    if associated_token_account.data_is_empty() {
+        let create_ata_instruction = create_associated_token_account(
+            &payer.key(),
+            &owner,
+            &mint.key(),
+            &token_program.key(),
+        );
+
+        invoke(
+            &create_ata_instruction,
+            &[
+                payer.to_account_info(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+                token_program.to_account_info(),
+                mint.to_account_info(),
+                associated_token_account.clone(),
+            ],
+        )?;
+    }
+
+    // Verify the account matches the expected address
+    require_keys_eq!(
+        associated_token_account.key(),
+        ata_address,
+        ErrorCode::AccountMismatch
+    );
+
+    Ok(())
+}
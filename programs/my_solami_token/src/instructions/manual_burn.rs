@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{
+    burn_tokens, close_emptied_holder_record, record_checkpoint, track_holder_balance,
+};
+use crate::errors::ErrorCode;
+use crate::events::BurnEvent;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct ManualBurn<'info> {
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = admin,
+    )]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of `admin_token_account`, created with
+    /// `admin` if missing
+    #[account(mut)]
+    pub checkpoints: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+    /// CHECK: `HolderRecord` PDA of `admin_token_account`, required while loyalty
+    /// tiers are set and when the burn empties the account
+    #[account(mut)]
+    pub holder_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: `rent_payer` of `holder_record`, refunded when the balance is emptied
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<ManualBurn>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    require!(
+        ctx.accounts.admin_token_account.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+
+    let admin = ctx.accounts.admin.to_account_info();
+    record_checkpoint(
+        &ctx.accounts.token_state,
+        ctx.accounts.checkpoints.as_deref(),
+        &ctx.accounts.admin_token_account,
+        Some(&admin),
+        ctx.accounts.system_program.as_deref(),
+    )?;
+
+    burn_tokens(
+        &ctx.accounts.mint,
+        &ctx.accounts.admin_token_account,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.token_program,
+        &[],
+        amount,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    track_holder_balance(
+        &ctx.accounts.token_state,
+        ctx.accounts.holder_record.as_deref(),
+        &mut ctx.accounts.admin_token_account,
+        now,
+    )?;
+    close_emptied_holder_record(
+        &mut ctx.accounts.token_state,
+        ctx.accounts.holder_record.as_deref(),
+        ctx.accounts.rent_recipient.as_deref(),
+        &ctx.accounts.admin_token_account,
+        now,
+    )?;
+
+    ctx.accounts.token_state.total_supply = ctx.accounts.token_state.total_supply
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    ctx.accounts.token_state.total_burned += amount;
+
+    emit!(BurnEvent {
+        burner: ctx.accounts.admin.key(),
+        amount,
+        timestamp: now,
+        new_total_supply: ctx.accounts.token_state.total_supply,
+    });
+
+    Ok(())
+}
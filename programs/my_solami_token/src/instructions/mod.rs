@@ -0,0 +1,57 @@
+mod shared;
+
+pub mod batch_transfer;
+pub mod bonding_curve;
+pub mod confidential;
+pub mod configure_emission;
+pub mod configure_launch_phases;
+pub mod configure_launch_tax;
+pub mod configure_loyalty_tiers;
+pub mod emit_epoch;
+pub mod finalize_supply;
+pub mod freeze_contract;
+pub mod governance;
+pub mod harvest_and_split_fees;
+pub mod initialize_token;
+pub mod manual_burn;
+pub mod prepare_rewards_swap;
+pub mod presale;
+pub mod reconcile_supply;
+pub mod referrals;
+pub mod register_holder;
+pub mod set_pool_address;
+pub mod snapshots;
+pub mod transfer_hook;
+pub mod transfer_ownership;
+pub mod transfer_tokens;
+pub mod update_tvl_data;
+pub mod user_burn;
+pub mod whitelist;
+
+pub use batch_transfer::*;
+pub use bonding_curve::*;
+pub use confidential::*;
+pub use configure_emission::*;
+pub use configure_launch_phases::*;
+pub use configure_launch_tax::*;
+pub use configure_loyalty_tiers::*;
+pub use emit_epoch::*;
+pub use finalize_supply::*;
+pub use freeze_contract::*;
+pub use governance::*;
+pub use harvest_and_split_fees::*;
+pub use initialize_token::*;
+pub use manual_burn::*;
+pub use prepare_rewards_swap::*;
+pub use presale::*;
+pub use reconcile_supply::*;
+pub use referrals::*;
+pub use register_holder::*;
+pub use set_pool_address::*;
+pub use snapshots::*;
+pub use transfer_hook::*;
+pub use transfer_ownership::*;
+pub use transfer_tokens::*;
+pub use update_tvl_data::*;
+pub use user_burn::*;
+pub use whitelist::*;
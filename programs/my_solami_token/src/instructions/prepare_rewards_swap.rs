@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{set_transfer_hook_program, transfer_within_program};
+use crate::errors::ErrorCode;
+use crate::events::SwapEvent;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct SwapRewards<'info> {
+    #[account(mut, token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub swap_wallet: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = rewards_pool,
+        has_one = swap_wallet,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    /// CHECK: PDA holding the transfer hook authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn handler(
+    ctx: Context<SwapRewards>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    require!(
+        ctx.accounts.rewards_pool.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+    let hook_enabled = ctx.accounts.token_state.transfer_hook_enabled;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            None,
+        )?;
+    }
+
+    transfer_within_program(
+        &ctx.accounts.rewards_pool.to_account_info(),
+        &ctx.accounts.swap_wallet.to_account_info(),
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &[],
+        amount,
+    )?;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            Some(crate::ID),
+        )?;
+    }
+
+    emit!(SwapEvent {
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+        pool: ctx.accounts.rewards_pool.key(),
+    });
+
+    Ok(())
+}
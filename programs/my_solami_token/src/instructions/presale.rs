@@ -0,0 +1,429 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{record_checkpoint, set_transfer_hook_program, transfer_within_program};
+use crate::errors::ErrorCode;
+use crate::events::{
+    PresaleClaimEvent, PresaleContributionEvent, PresaleFinalizedEvent, PresaleRefundEvent,
+};
+use crate::state::{Presale, PresaleReceipt, TokenState};
+use crate::tokenomics;
+
+#[derive(Accounts)]
+pub struct ConfigurePresale<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Presale::SIZE,
+        seeds = [b"presale", mint.key().as_ref()],
+        bump,
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ContributePresale<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale", mint.key().as_ref()],
+        bump = presale.bump,
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = 8 + PresaleReceipt::SIZE,
+        seeds = [b"presale_receipt", presale.key().as_ref(), contributor.key().as_ref()],
+        bump,
+    )]
+    pub receipt: Account<'info, PresaleReceipt>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimPresale<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = presale_escrow,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        seeds = [b"presale", mint.key().as_ref()],
+        bump = presale.bump,
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        close = contributor,
+        seeds = [b"presale_receipt", presale.key().as_ref(), contributor.key().as_ref()],
+        bump = receipt.bump,
+    )]
+    pub receipt: Account<'info, PresaleReceipt>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = contributor,
+    )]
+    pub contributor_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub presale_escrow: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA holding the transfer hook authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of `contributor_token_account`, created
+    /// with `contributor` if missing
+    #[account(mut)]
+    pub checkpoints: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+pub struct RefundPresale<'info> {
+    #[account(
+        mut,
+        seeds = [b"presale", mint.key().as_ref()],
+        bump = presale.bump,
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(
+        mut,
+        close = contributor,
+        seeds = [b"presale_receipt", presale.key().as_ref(), contributor.key().as_ref()],
+        bump = receipt.bump,
+    )]
+    pub receipt: Account<'info, PresaleReceipt>,
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizePresale<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = presale_escrow,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        mut,
+        seeds = [b"presale", mint.key().as_ref()],
+        bump = presale.bump,
+    )]
+    pub presale: Account<'info, Presale>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    #[account(mut, token::mint = mint)]
+    pub admin_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub presale_escrow: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA holding the transfer hook authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of `admin_token_account`, created with
+    /// `admin` if missing
+    #[account(mut)]
+    pub checkpoints: Option<UncheckedAccount<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+pub(crate) fn configure_presale(
+    ctx: Context<ConfigurePresale>,
+    tokens_per_sol: u64,
+    soft_cap: u64,
+    hard_cap: u64,
+    wallet_cap: u64,
+    start_time: i64,
+    end_time: i64,
+) -> Result<()> {
+    let tranche = ctx.accounts.token_state.presale_tranche;
+
+    require!(tranche > 0, ErrorCode::InvalidPresale);
+    require!(
+        tokens_per_sol > 0 && wallet_cap > 0 && hard_cap > 0 && soft_cap <= hard_cap,
+        ErrorCode::InvalidPresale
+    );
+    require!(start_time < end_time, ErrorCode::InvalidPresale);
+
+    // Selling out the hard cap must not promise more than the escrow holds
+    require!(
+        tokenomics::presale_tokens(hard_cap, tokens_per_sol)
+            .is_some_and(|tokens| tokens <= tranche),
+        ErrorCode::InvalidPresale
+    );
+
+    ctx.accounts.presale.set_inner(Presale {
+        mint: ctx.accounts.mint.key(),
+        tokens_per_sol,
+        soft_cap,
+        hard_cap,
+        wallet_cap,
+        start_time,
+        end_time,
+        total_raised: 0,
+        contributors: 0,
+        finalized: false,
+        bump: ctx.bumps.presale,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn contribute_presale(ctx: Context<ContributePresale>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale = &mut ctx.accounts.presale;
+    let receipt = &mut ctx.accounts.receipt;
+
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        presale.start_time <= now && now < presale.end_time,
+        ErrorCode::PresaleNotActive
+    );
+
+    let total_raised = presale
+        .total_raised
+        .checked_add(amount)
+        .filter(|total| *total <= presale.hard_cap)
+        .ok_or(ErrorCode::PresaleCapExceeded)?;
+    let contributed = receipt
+        .contributed
+        .checked_add(amount)
+        .filter(|total| *total <= presale.wallet_cap)
+        .ok_or(ErrorCode::PresaleCapExceeded)?;
+
+    // A fresh receipt has nothing contributed yet
+    if receipt.contributed == 0 {
+        receipt.presale = presale.key();
+        receipt.contributor = ctx.accounts.contributor.key();
+        receipt.bump = ctx.bumps.receipt;
+        presale.contributors = presale
+            .contributors
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    // The presale PDA's own lamports are the vault
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.contributor.to_account_info(),
+                to: presale.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    presale.total_raised = total_raised;
+    receipt.contributed = contributed;
+
+    emit!(PresaleContributionEvent {
+        mint: ctx.accounts.mint.key(),
+        contributor: ctx.accounts.contributor.key(),
+        amount,
+        total_contributed: contributed,
+        total_raised,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_presale(ctx: Context<ClaimPresale>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale = &ctx.accounts.presale;
+
+    require!(now >= presale.end_time, ErrorCode::PresaleNotEnded);
+    require!(presale.soft_cap_met(), ErrorCode::PresaleSoftCapNotMet);
+
+    let tokens =
+        tokenomics::presale_tokens(ctx.accounts.receipt.contributed, presale.tokens_per_sol)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    let contributor = ctx.accounts.contributor.to_account_info();
+    record_checkpoint(
+        &ctx.accounts.token_state,
+        ctx.accounts.checkpoints.as_deref(),
+        &ctx.accounts.contributor_token_account,
+        Some(&contributor),
+        ctx.accounts.system_program.as_deref(),
+    )?;
+
+    release_from_escrow(
+        &ctx.accounts.token_state,
+        presale,
+        &ctx.accounts.presale_escrow,
+        &ctx.accounts.contributor_token_account,
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        tokens,
+    )?;
+
+    emit!(PresaleClaimEvent {
+        mint: ctx.accounts.mint.key(),
+        contributor: ctx.accounts.contributor.key(),
+        contributed: ctx.accounts.receipt.contributed,
+        tokens,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn refund_presale(ctx: Context<RefundPresale>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale = &ctx.accounts.presale;
+
+    require!(now >= presale.end_time, ErrorCode::PresaleNotEnded);
+    require!(!presale.soft_cap_met(), ErrorCode::PresaleSoftCapMet);
+
+    let amount = ctx.accounts.receipt.contributed;
+    ctx.accounts.presale.sub_lamports(amount)?;
+    ctx.accounts.contributor.add_lamports(amount)?;
+
+    emit!(PresaleRefundEvent {
+        mint: ctx.accounts.mint.key(),
+        contributor: ctx.accounts.contributor.key(),
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn finalize_presale(ctx: Context<FinalizePresale>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let presale = &ctx.accounts.presale;
+
+    require!(now >= presale.end_time, ErrorCode::PresaleNotEnded);
+    require!(!presale.finalized, ErrorCode::PresaleFinalized);
+
+    let soft_cap_met = presale.soft_cap_met();
+    let tranche = ctx.accounts.token_state.presale_tranche;
+    // Receipts floor individually, so selling at the total never undercounts them
+    let (proceeds, sold) = if soft_cap_met {
+        let sold = tokenomics::presale_tokens(presale.total_raised, presale.tokens_per_sol)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        (presale.total_raised, sold)
+    } else {
+        // Contributions stay in the vault for refunds
+        (0, 0)
+    };
+    let unsold = tranche.saturating_sub(sold);
+
+    if proceeds > 0 {
+        ctx.accounts.presale.sub_lamports(proceeds)?;
+        ctx.accounts.admin.add_lamports(proceeds)?;
+    }
+
+    if unsold > 0 {
+        let admin = ctx.accounts.admin.to_account_info();
+        record_checkpoint(
+            &ctx.accounts.token_state,
+            ctx.accounts.checkpoints.as_deref(),
+            &ctx.accounts.admin_token_account,
+            Some(&admin),
+            ctx.accounts.system_program.as_deref(),
+        )?;
+        release_from_escrow(
+            &ctx.accounts.token_state,
+            &ctx.accounts.presale,
+            &ctx.accounts.presale_escrow,
+            &ctx.accounts.admin_token_account,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            unsold,
+        )?;
+    }
+
+    ctx.accounts.presale.finalized = true;
+
+    emit!(PresaleFinalizedEvent {
+        mint: ctx.accounts.mint.key(),
+        soft_cap_met,
+        proceeds,
+        unsold_tokens: unsold,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Move escrowed presale tokens, signed by the `presale` PDA
+#[allow(clippy::too_many_arguments)]
+fn release_from_escrow<'info>(
+    token_state: &TokenState,
+    presale: &Account<'info, Presale>,
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    recipient: &InterfaceAccount<'info, TokenAccount>,
+    mint_authority: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    let mint_key = mint.key();
+    let hook_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[token_state.mint_authority_bump],
+    ]];
+    let presale_seeds: &[&[&[u8]]] = &[&[b"presale", mint_key.as_ref(), &[presale.bump]]];
+
+    if token_state.transfer_hook_enabled {
+        set_transfer_hook_program(mint, mint_authority, token_program, hook_seeds, None)?;
+    }
+
+    transfer_within_program(
+        &escrow.to_account_info(),
+        &recipient.to_account_info(),
+        &presale.to_account_info(),
+        mint,
+        token_program,
+        presale_seeds,
+        amount,
+    )?;
+
+    if token_state.transfer_hook_enabled {
+        set_transfer_hook_program(
+            mint,
+            mint_authority,
+            token_program,
+            hook_seeds,
+            Some(crate::ID),
+        )?;
+    }
+
+    Ok(())
+}
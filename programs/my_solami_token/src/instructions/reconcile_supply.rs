@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::events::SupplyDiscrepancyEvent;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct ReconcileSupply<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = lp_pool,
+        has_one = rewards_pool,
+        has_one = staking_pool,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub lp_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub staking_pool: InterfaceAccount<'info, TokenAccount>,
+    /// Any signer; only the admin may correct pool balances
+    pub authority: Signer<'info>,
+}
+
+pub(crate) fn handler(ctx: Context<ReconcileSupply>, correct: bool) -> Result<()> {
+    let token_state = &ctx.accounts.token_state;
+    let actual_supply = ctx.accounts.mint.supply;
+    let actual_liquidity_pool = ctx.accounts.lp_pool.amount;
+    let actual_rewards_pool = ctx.accounts.rewards_pool.amount;
+    let actual_staking_pool = ctx.accounts.staking_pool.amount;
+
+    let supply_matches = token_state.total_supply == actual_supply;
+    let pools_match = token_state.liquidity_pool_balance == actual_liquidity_pool
+        && token_state.rewards_pool_balance == actual_rewards_pool
+        && token_state.staking_pool_balance == actual_staking_pool;
+    if supply_matches && pools_match {
+        return Ok(());
+    }
+
+    let mut event = SupplyDiscrepancyEvent {
+        mint: ctx.accounts.mint.key(),
+        recorded_supply: token_state.total_supply,
+        actual_supply,
+        recorded_burned: token_state.total_burned,
+        recorded_liquidity_pool: token_state.liquidity_pool_balance,
+        actual_liquidity_pool,
+        recorded_rewards_pool: token_state.rewards_pool_balance,
+        actual_rewards_pool,
+        recorded_staking_pool: token_state.staking_pool_balance,
+        actual_staking_pool,
+        corrected: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    };
+
+    if correct {
+        let is_admin = ctx.accounts.authority.key() == ctx.accounts.token_state.admin;
+        let token_state = &mut ctx.accounts.token_state;
+
+        // The mint is bound to token_state by its seeds, so anyone may resync the supply
+        if !supply_matches {
+            // Supply missing from the records was burned without being counted
+            if token_state.total_supply > actual_supply {
+                token_state.total_burned = token_state.total_burned
+                    .checked_add(token_state.total_supply - actual_supply)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            token_state.total_supply = actual_supply;
+            event.corrected = true;
+        }
+
+        // Recorded pool balances feed the TVL figures, so only the admin may resync them
+        if !pools_match && is_admin {
+            token_state.update_liquidity_pool(actual_liquidity_pool)?;
+            token_state.update_rewards_pool(actual_rewards_pool)?;
+            token_state.update_staking_pool(actual_staking_pool)?;
+            event.corrected = true;
+        }
+    }
+
+    emit!(event);
+    Ok(())
+}
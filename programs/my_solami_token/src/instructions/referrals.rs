@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{record_checkpoint, set_transfer_hook_program, transfer_within_program};
+use crate::errors::ErrorCode;
+use crate::events::{ReferralClaimEvent, ReferralRegisteredEvent};
+use crate::state::{ReferralRecord, ReferrerStats, TokenState};
+
+#[derive(Accounts)]
+pub struct ConfigureReferrals<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(token::mint = mint)]
+    pub referral_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA that owns the referral vault
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = 8 + ReferralRecord::SIZE,
+        seeds = [b"referral", mint.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub referral: Account<'info, ReferralRecord>,
+    /// CHECK: Any wallet other than `user` can be a referrer
+    pub referrer: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + ReferrerStats::SIZE,
+        seeds = [b"referrer", mint.key().as_ref(), referrer.key().as_ref()],
+        bump,
+    )]
+    pub referrer_stats: Account<'info, ReferrerStats>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = referral_vault,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        mut,
+        seeds = [b"referrer", mint.key().as_ref(), referrer.key().as_ref()],
+        bump = referrer_stats.bump,
+    )]
+    pub referrer_stats: Account<'info, ReferrerStats>,
+    pub referrer: Signer<'info>,
+    #[account(mut, token::mint = mint)]
+    pub referrer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub referral_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: PDA that owns the referral vault
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of `referrer_token_account`, created with
+    /// `payer` if missing
+    #[account(mut)]
+    pub checkpoints: Option<UncheckedAccount<'info>>,
+    /// Pays for new checkpoints
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+}
+
+pub(crate) fn configure_referrals(
+    ctx: Context<ConfigureReferrals>,
+    referral_bps: u16,
+) -> Result<()> {
+    require!(referral_bps <= 10_000, ErrorCode::InvalidReferralConfig);
+    require_keys_eq!(
+        ctx.accounts.referral_vault.owner,
+        ctx.accounts.mint_authority.key(),
+        ErrorCode::InvalidReferralConfig
+    );
+
+    // Unclaimed rewards stay in the first vault, so it can't be swapped out
+    let token_state = &mut ctx.accounts.token_state;
+    require!(
+        token_state.referral_vault == Pubkey::default()
+            || token_state.referral_vault == ctx.accounts.referral_vault.key(),
+        ErrorCode::InvalidReferralConfig
+    );
+
+    token_state.referral_vault = ctx.accounts.referral_vault.key();
+    token_state.referral_bps = referral_bps;
+
+    Ok(())
+}
+
+pub(crate) fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let user = ctx.accounts.user.key();
+    let referrer = ctx.accounts.referrer.key();
+    require_keys_neq!(user, referrer, ErrorCode::SelfReferral);
+    // A token account or PDA the user controls would pass the key check, so the
+    // referrer must be a wallet, owned by the system program
+    require_keys_eq!(
+        *ctx.accounts.referrer.owner,
+        system_program::ID,
+        ErrorCode::InvalidReferrer
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let mint = ctx.accounts.mint.key();
+
+    ctx.accounts.referral.set_inner(ReferralRecord {
+        mint,
+        user,
+        referrer,
+        registered_at: now,
+        bump: ctx.bumps.referral,
+    });
+
+    let stats = &mut ctx.accounts.referrer_stats;
+    if stats.referrer == Pubkey::default() {
+        stats.mint = mint;
+        stats.referrer = referrer;
+        stats.bump = ctx.bumps.referrer_stats;
+    }
+    stats.referred_count = stats
+        .referred_count
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit!(ReferralRegisteredEvent {
+        mint,
+        user,
+        referrer,
+        referred_count: stats.referred_count,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    let amount = ctx.accounts.referrer_stats.claimable;
+    require!(amount > 0, ErrorCode::NothingToClaim);
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.accounts.token_state.mint_authority_bump],
+    ]];
+    record_checkpoint(
+        &ctx.accounts.token_state,
+        ctx.accounts.checkpoints.as_deref(),
+        &ctx.accounts.referrer_token_account,
+        ctx.accounts.payer.as_deref(),
+        ctx.accounts.system_program.as_deref(),
+    )?;
+    let hook_enabled = ctx.accounts.token_state.transfer_hook_enabled;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            None,
+        )?;
+    }
+
+    transfer_within_program(
+        &ctx.accounts.referral_vault.to_account_info(),
+        &ctx.accounts.referrer_token_account.to_account_info(),
+        &ctx.accounts.mint_authority,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        signer_seeds,
+        amount,
+    )?;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            Some(crate::ID),
+        )?;
+    }
+
+    ctx.accounts.referrer_stats.claimable = 0;
+
+    emit!(ReferralClaimEvent {
+        mint: mint_key,
+        referrer: ctx.accounts.referrer.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::events::HolderEvent;
+use crate::state::{HolderRecord, TokenState};
+
+#[derive(Accounts)]
+pub struct RegisterHolder<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        token::mint = mint,
+        constraint = token_account.amount > 0 @ ErrorCode::InsufficientBalance,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + HolderRecord::SIZE,
+        seeds = [b"holder", mint.key().as_ref(), token_account.key().as_ref()],
+        bump,
+    )]
+    pub holder_record: Account<'info, HolderRecord>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn handler(ctx: Context<RegisterHolder>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let mint = ctx.accounts.mint.key();
+    let owner = ctx.accounts.token_account.owner;
+
+    ctx.accounts.holder_record.set_inner(HolderRecord {
+        mint,
+        token_account: ctx.accounts.token_account.key(),
+        owner,
+        first_seen: now,
+        hold_start: None,
+        rent_payer: ctx.accounts.payer.key(),
+        bump: ctx.bumps.holder_record,
+    });
+    ctx.accounts.holder_record.track_hold(
+        ctx.accounts.token_account.amount,
+        ctx.accounts.token_state.loyalty_min_balance,
+        now,
+    );
+
+    let token_state = &mut ctx.accounts.token_state;
+    token_state.holder_count = token_state.holder_count
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit!(HolderEvent {
+        mint,
+        owner,
+        is_added: true,
+        holder_count: token_state.holder_count,
+        timestamp: now,
+    });
+
+    Ok(())
+}
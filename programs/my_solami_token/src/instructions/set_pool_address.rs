@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::events::PoolAddressUpdatedEvent;
+use crate::state::{PoolKind, TokenState};
+
+#[derive(Accounts)]
+pub struct SetPoolAddress<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub new_pool: InterfaceAccount<'info, TokenAccount>,
+}
+
+pub(crate) fn handler(ctx: Context<SetPoolAddress>, pool: PoolKind) -> Result<()> {
+    let new_address = ctx.accounts.new_pool.key();
+    let old_address = ctx.accounts.token_state.set_pool(pool, new_address);
+
+    emit!(PoolAddressUpdatedEvent {
+        mint: ctx.accounts.mint.key(),
+        pool,
+        old_address,
+        new_address,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,667 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use anchor_spl::token_interface::{
+    self, Burn, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface, TransferChecked,
+    TransferHookUpdate,
+};
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+
+use crate::errors::ErrorCode;
+use crate::events::{HolderEvent, ReferralRewardEvent, SnapshotEvent};
+use crate::state::{
+    BalanceCheckpoints, HolderRecord, ReferralRecord, ReferrerStats, Snapshot, TokenState,
+    Whitelist,
+};
+use crate::tokenomics;
+
+/// Accounts moved by `allocate_tax`
+#[derive(Clone)]
+pub struct AllocateTaxAccounts<'info> {
+    pub sender: InterfaceAccount<'info, TokenAccount>,
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    pub lp_fund: InterfaceAccount<'info, TokenAccount>,
+    /// Receives the referral share of the split, when there is one
+    pub referral_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Owner or delegate of `sender`
+    pub sender_authority: AccountInfo<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Mint tokens to specified account, signed by the `mint_authority` PDA
+pub fn mint_tokens<'info>(
+    token_state: &TokenState,
+    mint: &AccountInfo<'info>,
+    recipient: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    require!(!token_state.supply_finalized, ErrorCode::SupplyFinalized);
+
+    let cpi_accounts = MintTo {
+        mint: mint.clone(),
+        to: recipient.clone(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::mint_to(cpi_ctx, amount)
+}
+
+/// Clear an authority on the mint held by the `mint_authority` PDA
+pub fn revoke_mint_authority<'info>(
+    mint: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+    authority_type: AuthorityType,
+) -> Result<()> {
+    let cpi_accounts = SetAuthority {
+        current_authority: authority.clone(),
+        account_or_mint: mint.clone(),
+    };
+    token_interface::set_authority(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ),
+        authority_type,
+        None,
+    )
+}
+
+/// Point the mint's transfer hook at `program_id`, signed by the hook authority PDA.
+///
+/// The runtime rejects A -> B -> A reentrancy, so program-driven transfers of a
+/// hooked mint clear the hook first and restore it once they are done.
+pub fn set_transfer_hook_program<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+    program_id: Option<Pubkey>,
+) -> Result<()> {
+    let cpi_accounts = TransferHookUpdate {
+        token_program_id: token_program.to_account_info(),
+        mint: mint.to_account_info(),
+        authority: authority.clone(),
+    };
+    token_interface::transfer_hook_update(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ),
+        program_id,
+    )
+}
+
+/// Burn tokens from specified account
+pub fn burn_tokens<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Burn {
+        mint: mint.to_account_info(),
+        from: account.to_account_info(),
+        authority: authority.clone(),
+    };
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+/// Calculate transfer amounts with tax less a loyalty discount, returning
+/// (net, tax, tax rate in basis points)
+pub fn calculate_transfer_amounts(
+    token_state: &TokenState,
+    whitelist: &Whitelist,
+    receiver: &Pubkey,
+    amount: u64,
+    now: i64,
+    discount_bps: u16,
+) -> Result<(u64, u64, u16)> {
+    let tax_bps = if whitelist.contains(receiver) {
+        0
+    } else {
+        tokenomics::discounted_tax_bps(token_state.tax_rate_bps(now), discount_bps)
+    };
+    let (net, tax) = tokenomics::transfer_amounts_at(amount, tax_bps);
+    Ok((net, tax, tax_bps))
+}
+
+/// Handle tax allocation of a split; the caller records `split.burn`
+pub fn allocate_tax(
+    ctx: AllocateTaxAccounts,
+    signer_seeds: &[&[&[u8]]],
+    split: &tokenomics::TaxSplit,
+) -> Result<()> {
+    let tokenomics::TaxSplit { rewards, lp_fund, burn: burn_amount, referral } = *split;
+
+    // Distribute to rewards pool
+    transfer_within_program(
+        &ctx.sender.to_account_info(),
+        &ctx.rewards_pool.to_account_info(),
+        &ctx.sender_authority.to_account_info(),
+        &ctx.mint,
+        &ctx.token_program,
+        signer_seeds,
+        rewards,
+    )?;
+
+    // Add to LP fund
+    transfer_within_program(
+        &ctx.sender.to_account_info(),
+        &ctx.lp_fund.to_account_info(),
+        &ctx.sender_authority.to_account_info(),
+        &ctx.mint,
+        &ctx.token_program,
+        signer_seeds,
+        lp_fund,
+    )?;
+
+    // Referral share waits in the vault until the referrer claims it
+    if referral > 0 {
+        let referral_vault = ctx.referral_vault
+            .as_ref()
+            .ok_or(ErrorCode::MissingReferralAccounts)?;
+        transfer_within_program(
+            &ctx.sender.to_account_info(),
+            &referral_vault.to_account_info(),
+            &ctx.sender_authority.to_account_info(),
+            &ctx.mint,
+            &ctx.token_program,
+            signer_seeds,
+            referral,
+        )?;
+    }
+
+    // Burn portion
+    burn_tokens(
+        &ctx.mint,
+        &ctx.sender,
+        &ctx.sender_authority,
+        &ctx.token_program,
+        signer_seeds,
+        burn_amount,
+    )?;
+
+    Ok(())
+}
+
+/// Check the referral accounts of a transfer from `sender_owner`, returning
+/// whether it has a referrer to credit.
+///
+/// Once referrals are configured the sender's `ReferralRecord` PDA is required,
+/// empty when no referrer was registered, so a referred sender can't skip its
+/// referrer by leaving the record out.
+pub fn check_referral(
+    token_state: &TokenState,
+    mint: &Pubkey,
+    sender_owner: &Pubkey,
+    referral: Option<&AccountInfo>,
+    referrer_stats: Option<&ReferrerStats>,
+    referral_vault: Option<&Pubkey>,
+) -> Result<bool> {
+    if token_state.referral_vault == Pubkey::default() {
+        return Ok(false);
+    }
+
+    let referral = referral.ok_or(ErrorCode::MissingReferralAccounts)?;
+    let (address, _) = ReferralRecord::find_address(mint, sender_owner);
+    require_keys_eq!(referral.key(), address, ErrorCode::AccountMismatch);
+    if referral.data_is_empty() {
+        return Ok(false);
+    }
+    let record = ReferralRecord::try_deserialize(&mut &referral.try_borrow_data()?[..])?;
+
+    let (Some(stats), Some(vault)) = (referrer_stats, referral_vault) else {
+        return err!(ErrorCode::MissingReferralAccounts);
+    };
+    require!(
+        stats.mint == *mint
+            && stats.referrer == record.referrer
+            && *vault == token_state.referral_vault,
+        ErrorCode::AccountMismatch
+    );
+    Ok(true)
+}
+
+/// Record the tax a referred `user` paid and credit the referrer's share
+pub fn credit_referrer(
+    stats: &mut ReferrerStats,
+    user: &Pubkey,
+    tax_amount: u64,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    stats.referred_tax = stats.referred_tax
+        .checked_add(tax_amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    stats.total_earned = stats.total_earned
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    stats.claimable = stats.claimable
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    if amount > 0 {
+        emit!(ReferralRewardEvent {
+            mint: stats.mint,
+            referrer: stats.referrer,
+            user: *user,
+            tax_amount,
+            amount,
+            claimable: stats.claimable,
+            timestamp: now,
+        });
+    }
+
+    Ok(())
+}
+
+/// Transfer tokens within the program
+pub fn transfer_within_program<'info>(
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = TransferChecked {
+        from: from.clone(),
+        mint: mint.to_account_info(),
+        to: to.clone(),
+        authority: authority.clone(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)
+}
+
+/// Create the `HolderRecord` of `token_account` unless it exists, returning whether it was created
+pub fn open_holder_record<'info>(
+    record: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    owner: &Pubkey,
+    now: i64,
+) -> Result<bool> {
+    let (address, bump) = HolderRecord::find_address(mint, token_account);
+    require_keys_eq!(record.key(), address, ErrorCode::AccountMismatch);
+    if !record.data_is_empty() {
+        return Ok(false);
+    }
+
+    create_program_account(
+        record,
+        payer,
+        system_program,
+        8 + HolderRecord::SIZE,
+        &[b"holder", mint.as_ref(), token_account.as_ref(), &[bump]],
+    )?;
+
+    let holder = HolderRecord {
+        mint: *mint,
+        token_account: *token_account,
+        owner: *owner,
+        first_seen: now,
+        hold_start: Some(now),
+        rent_payer: payer.key(),
+        bump,
+    };
+    let mut data = record.try_borrow_mut_data()?;
+    holder.try_serialize(&mut &mut data[..])?;
+    Ok(true)
+}
+
+/// Start or break the continuous hold of `token_account` once an instruction has
+/// moved its balance; its `HolderRecord` PDA is required while loyalty tiers are
+/// set, and an account without one has no hold to track
+pub fn track_holder_balance<'info>(
+    token_state: &TokenState,
+    record: Option<&AccountInfo<'info>>,
+    token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    now: i64,
+) -> Result<()> {
+    let threshold = token_state.loyalty_min_balance;
+    let Some(record) = record else {
+        // Skipping the record would keep a hold the instruction should break
+        require!(threshold == 0, ErrorCode::MissingHolderAccounts);
+        return Ok(());
+    };
+    let (address, _) = HolderRecord::find_address(&token_account.mint, &token_account.key());
+    require_keys_eq!(record.key(), address, ErrorCode::AccountMismatch);
+    if record.data_is_empty() {
+        return Ok(());
+    }
+
+    token_account.reload()?;
+    let mut data = record.try_borrow_mut_data()?;
+    let mut holder = HolderRecord::try_deserialize(&mut &data[..])?;
+    holder.track_hold(token_account.amount, threshold, now);
+    holder.try_serialize(&mut &mut data[..])
+}
+
+/// Close the `HolderRecord` of `token_account` once an instruction has emptied
+/// it, refunding `rent_recipient` and dropping the holder from `holder_count`;
+/// runs after `track_holder_balance` has reloaded the balance
+pub fn close_emptied_holder_record<'info>(
+    token_state: &mut TokenState,
+    record: Option<&AccountInfo<'info>>,
+    rent_recipient: Option<&AccountInfo<'info>>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    now: i64,
+) -> Result<()> {
+    if token_account.amount > 0 {
+        return Ok(());
+    }
+
+    // Skipping the record would keep an emptied holder counted
+    let record = record.ok_or(ErrorCode::MissingHolderAccounts)?;
+    let (address, _) = HolderRecord::find_address(&token_account.mint, &token_account.key());
+    require_keys_eq!(record.key(), address, ErrorCode::AccountMismatch);
+    if record.data_is_empty() {
+        return Ok(());
+    }
+
+    let holder = {
+        let data = record.try_borrow_data()?;
+        HolderRecord::try_deserialize(&mut &data[..])?
+    };
+    let rent_recipient = rent_recipient.ok_or(ErrorCode::MissingHolderAccounts)?;
+    require_keys_eq!(
+        rent_recipient.key(),
+        holder.rent_payer,
+        ErrorCode::AccountMismatch
+    );
+    close_program_account(record, rent_recipient)?;
+
+    token_state.holder_count = token_state.holder_count
+        .checked_sub(1)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    emit!(HolderEvent {
+        mint: holder.mint,
+        owner: holder.owner,
+        is_added: false,
+        holder_count: token_state.holder_count,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Create the `BalanceCheckpoints` of `token_account` unless it exists
+pub fn open_checkpoints<'info>(
+    checkpoints: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    token_account: &Pubkey,
+    snapshot_id: u64,
+) -> Result<()> {
+    let (address, bump) = BalanceCheckpoints::find_address(token_account);
+    require_keys_eq!(checkpoints.key(), address, ErrorCode::AccountMismatch);
+    if !checkpoints.data_is_empty() {
+        return Ok(());
+    }
+
+    create_program_account(
+        checkpoints,
+        payer,
+        system_program,
+        8 + BalanceCheckpoints::SIZE,
+        &[b"checkpoints", token_account.as_ref(), &[bump]],
+    )?;
+
+    let record = BalanceCheckpoints {
+        token_account: *token_account,
+        checkpoints: Vec::new(),
+        pruned_through: 0,
+        opened_at: snapshot_id,
+        bump,
+    };
+    let mut data = checkpoints.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])
+}
+
+/// Checkpoint the balance of `token_account` before it changes, once a snapshot
+/// exists, creating its `BalanceCheckpoints` with `payer` if missing
+pub fn record_checkpoint<'info>(
+    token_state: &TokenState,
+    checkpoints: Option<&AccountInfo<'info>>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    payer: Option<&AccountInfo<'info>>,
+    system_program: Option<&AccountInfo<'info>>,
+) -> Result<()> {
+    let snapshot_id = token_state.snapshot_id;
+    if snapshot_id == 0 {
+        return Ok(());
+    }
+
+    // Skipping the account would let a balance change go unrecorded
+    let checkpoints = checkpoints.ok_or(ErrorCode::MissingCheckpointAccounts)?;
+    if checkpoints.data_is_empty() {
+        let (Some(payer), Some(system_program)) = (payer, system_program) else {
+            return err!(ErrorCode::MissingCheckpointAccounts);
+        };
+        open_checkpoints(
+            checkpoints,
+            payer,
+            system_program,
+            &token_account.key(),
+            snapshot_id,
+        )?;
+    }
+    write_checkpoint(
+        checkpoints,
+        &token_account.key(),
+        snapshot_id,
+        token_account.amount,
+    )
+}
+
+/// Record `balance`, held by `token_account` before a direct transfer moved it,
+/// if its `BalanceCheckpoints` are open; the transfer hook has no payer to open them
+pub fn record_direct_checkpoint(
+    token_state: &TokenState,
+    checkpoints: &AccountInfo,
+    token_account: &Pubkey,
+    balance: u64,
+) -> Result<()> {
+    if token_state.snapshot_id == 0 || checkpoints.data_is_empty() {
+        return Ok(());
+    }
+    write_checkpoint(checkpoints, token_account, token_state.snapshot_id, balance)
+}
+
+fn write_checkpoint(
+    checkpoints: &AccountInfo,
+    token_account: &Pubkey,
+    snapshot_id: u64,
+    balance: u64,
+) -> Result<()> {
+    // Only this program creates accounts it owns with a matching `token_account`
+    require_keys_eq!(*checkpoints.owner, crate::ID, ErrorCode::AccountMismatch);
+    let mut data = checkpoints.try_borrow_mut_data()?;
+    let mut history = BalanceCheckpoints::try_deserialize(&mut &data[..])?;
+    require_keys_eq!(history.token_account, *token_account, ErrorCode::AccountMismatch);
+    history.record(snapshot_id, balance);
+    history.try_serialize(&mut &mut data[..])
+}
+
+/// Advance `token_state` to a new snapshot recorded in `snapshot`, returning its id
+pub fn open_snapshot(
+    token_state: &mut TokenState,
+    snapshot: &mut Account<Snapshot>,
+    mint: Pubkey,
+    bump: u8,
+) -> Result<u64> {
+    let clock = Clock::get()?;
+    let id = token_state
+        .snapshot_id
+        .checked_add(1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    token_state.snapshot_id = id;
+
+    snapshot.set_inner(Snapshot {
+        mint,
+        id,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+        bump,
+    });
+
+    emit!(SnapshotEvent {
+        mint,
+        snapshot_id: id,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(id)
+}
+
+/// Balance `token_account` held at snapshot `snapshot_id`, read from its
+/// `BalanceCheckpoints`; an empty account means it hasn't changed since
+pub fn snapshot_balance(
+    token_state: &TokenState,
+    checkpoints: &AccountInfo,
+    token_account: &InterfaceAccount<TokenAccount>,
+    snapshot_id: u64,
+) -> Result<u64> {
+    require!(
+        snapshot_id > 0 && snapshot_id <= token_state.snapshot_id,
+        ErrorCode::InvalidSnapshot
+    );
+    require!(
+        !token_state.is_pool(&token_account.key()),
+        ErrorCode::PoolNotSnapshotted
+    );
+    if checkpoints.data_is_empty() {
+        return Ok(token_account.amount);
+    }
+
+    let data = checkpoints.try_borrow_data()?;
+    let record = BalanceCheckpoints::try_deserialize(&mut &data[..])?;
+    require_keys_eq!(
+        record.token_account,
+        token_account.key(),
+        ErrorCode::AccountMismatch
+    );
+    Ok(record
+        .balance_at(snapshot_id, token_account.amount)
+        .ok_or(ErrorCode::SnapshotPruned)?)
+}
+
+/// Create a rent-exempt account of `space` bytes owned by this program at the
+/// PDA signed for by `seeds`
+fn create_program_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    // Lamports sent to the address beforehand must not block the account
+    let lamports = account.lamports();
+    if lamports == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                signer_seeds,
+            ),
+            rent,
+            space as u64,
+            &crate::ID,
+        )?;
+    } else {
+        if rent > lamports {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: account.clone(),
+                    },
+                ),
+                rent - lamports,
+            )?;
+        }
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: account.clone(),
+                },
+                signer_seeds,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Assign {
+                    account_to_assign: account.clone(),
+                },
+                signer_seeds,
+            ),
+            &crate::ID,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Close an account owned by this program, sending its lamports to `destination`
+fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    let lamports = account.lamports();
+    destination.add_lamports(lamports)?;
+    account.sub_lamports(lamports)?;
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// Close a `HolderRecord`, refunding its rent payer
+pub fn close_holder_record<'info>(
+    record: &Account<'info, HolderRecord>,
+    rent_recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    require_keys_eq!(
+        rent_recipient.key(),
+        record.rent_payer,
+        ErrorCode::AccountMismatch
+    );
+    record.close(rent_recipient.clone())
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use super::shared::{open_snapshot, snapshot_balance};
+use crate::errors::ErrorCode;
+use crate::state::{BalanceCheckpoints, Snapshot, TokenState};
+
+#[derive(Accounts)]
+pub struct TakeSnapshot<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Snapshot::SIZE,
+        seeds = [
+            b"snapshot",
+            mint.key().as_ref(),
+            &(token_state.snapshot_id + 1).to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub snapshot: Account<'info, Snapshot>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Opens the checkpoints of a token account ahead of a snapshot, so its balance
+/// at every later snapshot is proven
+#[derive(Accounts)]
+pub struct RegisterCheckpoints<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        token::mint = mint,
+        constraint = !token_state.is_pool(&token_account.key()) @ ErrorCode::PoolNotSnapshotted,
+    )]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BalanceCheckpoints::SIZE,
+        seeds = [b"checkpoints", token_account.key().as_ref()],
+        bump,
+    )]
+    pub checkpoints: Account<'info, BalanceCheckpoints>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BalanceAt<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(token::mint = mint)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: `BalanceCheckpoints` of `token_account`, empty until registered or
+    /// until an instruction changes its balance after a snapshot
+    #[account(
+        seeds = [b"checkpoints", token_account.key().as_ref()],
+        bump,
+    )]
+    pub checkpoints: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn take_snapshot(ctx: Context<TakeSnapshot>) -> Result<()> {
+    open_snapshot(
+        &mut ctx.accounts.token_state,
+        &mut ctx.accounts.snapshot,
+        ctx.accounts.mint.key(),
+        ctx.bumps.snapshot,
+    )?;
+    Ok(())
+}
+
+pub(crate) fn register_checkpoints(ctx: Context<RegisterCheckpoints>) -> Result<()> {
+    ctx.accounts.checkpoints.set_inner(BalanceCheckpoints {
+        token_account: ctx.accounts.token_account.key(),
+        checkpoints: Vec::new(),
+        pruned_through: 0,
+        opened_at: ctx.accounts.token_state.snapshot_id,
+        bump: ctx.bumps.checkpoints,
+    });
+    Ok(())
+}
+
+pub(crate) fn balance_at(ctx: Context<BalanceAt>, snapshot_id: u64) -> Result<u64> {
+    snapshot_balance(
+        &ctx.accounts.token_state,
+        &ctx.accounts.checkpoints,
+        &ctx.accounts.token_account,
+        snapshot_id,
+    )
+}
@@ -0,0 +1,256 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig,
+        transfer_hook::{self, TransferHookAccount},
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account as SplTokenAccount, Mint as SplMint},
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_tlv_account_resolution::{
+    account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList,
+};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use super::shared::record_direct_checkpoint;
+use crate::errors::ErrorCode;
+use crate::state::{TaxBackend, TokenState, Whitelist};
+
+#[derive(Accounts)]
+pub struct InitializeTransferHook<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    /// CHECK: Validation account written by `ExtraAccountMetaList::init`
+    #[account(
+        init,
+        payer = admin,
+        space = ExtraAccountMetaList::size_of(4).unwrap(),
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+    /// CHECK: PDA expected to be the mint's transfer hook authority
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts passed by Token-2022 to the transfer hook `Execute` instruction
+#[derive(Accounts)]
+pub struct TransferHookExecute<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Source owner or delegate, not required to sign the hook
+    pub owner: AccountInfo<'info>,
+    /// CHECK: Validation account holding the extra account metas
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump = token_state.whitelist_bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    /// CHECK: `BalanceCheckpoints` of `source_token`, recorded if open
+    #[account(
+        mut,
+        seeds = [b"checkpoints", source_token.key().as_ref()],
+        bump,
+    )]
+    pub source_checkpoints: UncheckedAccount<'info>,
+    /// CHECK: `BalanceCheckpoints` of `destination_token`, recorded if open
+    #[account(
+        mut,
+        seeds = [b"checkpoints", destination_token.key().as_ref()],
+        bump,
+    )]
+    pub destination_checkpoints: UncheckedAccount<'info>,
+}
+
+pub(crate) fn initialize_transfer_hook(ctx: Context<InitializeTransferHook>) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    // The mint must call back into this program and let the mint_authority
+    // PDA toggle the hook around program-driven transfers.
+    {
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+        require!(
+            transfer_hook::get_program_id(&mint) == Some(crate::ID),
+            ErrorCode::InvalidTransferHook
+        );
+        let hook = mint.get_extension::<transfer_hook::TransferHook>()?;
+        require!(
+            Option::<Pubkey>::from(hook.authority) == Some(ctx.accounts.mint_authority.key()),
+            ErrorCode::InvalidTransferHook
+        );
+    }
+
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+        &transfer_hook_extra_account_metas()?,
+    )?;
+
+    ctx.accounts.token_state.transfer_hook_enabled = true;
+    Ok(())
+}
+
+pub(crate) fn transfer_hook(ctx: Context<TransferHookExecute>, amount: u64) -> Result<()> {
+    assert_is_transferring(&ctx.accounts.source_token.to_account_info())?;
+
+    require!(!ctx.accounts.token_state.is_frozen, ErrorCode::ContractFrozen);
+
+    // token_state is read-only here, so phase changes are recorded by transfer_tokens
+    let whitelist = &ctx.accounts.whitelist;
+    let whitelisted = whitelist.contains(&ctx.accounts.source_token.owner)
+        || whitelist.contains(&ctx.accounts.destination_token.owner);
+    ctx.accounts.token_state.check_launch_phase(
+        Clock::get()?.unix_timestamp,
+        amount,
+        whitelisted,
+    )?;
+
+    // Direct transfers skip the program's tax split, so they are only
+    // allowed towards whitelisted (untaxed) receivers, unless Token-2022
+    // already withholds the tax through the transfer fee extension.
+    if ctx.accounts.token_state.tax_backend == TaxBackend::Program {
+        require!(
+            whitelist.contains(&ctx.accounts.destination_token.owner),
+            ErrorCode::TaxedTransferRequired
+        );
+    }
+
+    record_direct_checkpoints(ctx.accounts, amount)
+}
+
+/// Checkpoint the balances a direct transfer of `amount` moved, so snapshots
+/// stay provable for accounts with open checkpoints
+fn record_direct_checkpoints(accounts: &TransferHookExecute, amount: u64) -> Result<()> {
+    if accounts.token_state.snapshot_id == 0 {
+        return Ok(());
+    }
+
+    // The hook runs after Token-2022 moved the tokens, crediting the
+    // destination net of any withheld fee
+    let fee = withheld_fee(&accounts.mint.to_account_info(), amount)?;
+    let source = accounts.source_token.key();
+    let destination = accounts.destination_token.key();
+    if source == destination {
+        let before = accounts.source_token.amount
+            .checked_add(fee)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        return record_direct_checkpoint(
+            &accounts.token_state,
+            &accounts.source_checkpoints,
+            &source,
+            before,
+        );
+    }
+
+    let source_before = accounts.source_token.amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    record_direct_checkpoint(
+        &accounts.token_state,
+        &accounts.source_checkpoints,
+        &source,
+        source_before,
+    )?;
+    let destination_before = accounts.destination_token.amount
+        .checked_sub(amount - fee)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    record_direct_checkpoint(
+        &accounts.token_state,
+        &accounts.destination_checkpoints,
+        &destination,
+        destination_before,
+    )
+}
+
+/// Fee Token-2022 withholds from a transfer of `amount`, 0 without the extension
+fn withheld_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<SplMint>::unpack(&data)?;
+    let Ok(config) = mint.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    Ok(config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?)
+}
+
+/// Extra accounts resolved by Token-2022 when invoking the transfer hook
+fn transfer_hook_extra_account_metas() -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        // token_state: [b"token_state", mint]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"token_state".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        // whitelist: [b"whitelist", mint]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"whitelist".to_vec() },
+                Seed::AccountKey { index: 1 },
+            ],
+            false,
+            false,
+        )?,
+        // source_checkpoints: [b"checkpoints", source]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"checkpoints".to_vec() },
+                Seed::AccountKey { index: 0 },
+            ],
+            false,
+            true,
+        )?,
+        // destination_checkpoints: [b"checkpoints", destination]
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"checkpoints".to_vec() },
+                Seed::AccountKey { index: 2 },
+            ],
+            false,
+            true,
+        )?,
+    ])
+}
+
+/// Ensure the hook is being invoked by Token-2022 in the middle of a transfer
+fn assert_is_transferring(source_token: &AccountInfo) -> Result<()> {
+    let source_data = source_token.try_borrow_data()?;
+    let source = StateWithExtensions::<SplTokenAccount>::unpack(&source_data)?;
+    let extension = source.get_extension::<TransferHookAccount>()?;
+    require!(bool::from(extension.transferring), ErrorCode::NotTransferring);
+    Ok(())
+}
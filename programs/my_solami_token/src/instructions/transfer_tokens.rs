@@ -0,0 +1,396 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::shared::{
+    allocate_tax, calculate_transfer_amounts, check_referral, close_holder_record, credit_referrer,
+    open_holder_record, record_checkpoint, set_transfer_hook_program, transfer_within_program,
+    AllocateTaxAccounts,
+};
+use crate::errors::ErrorCode;
+use crate::events::{HolderEvent, LaunchPhaseChangedEvent, TransferEvent};
+use crate::state::{HolderRecord, LoyaltyTiers, ReferrerStats, TaxBackend, TokenState, Whitelist};
+use crate::tokenomics;
+
+#[derive(Accounts)]
+pub struct TransferTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = rewards_pool,
+        has_one = lp_fund,
+        has_one = lp_pool,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, token::mint = mint)]
+    pub sender: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub receiver: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_fund: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub lp_pool: InterfaceAccount<'info, TokenAccount>,
+
+    /// Owner of `sender`, or its approved delegate for `transfer_tokens_from`
+    pub authority: Signer<'info>,
+    /// CHECK: PDA that toggles the transfer hook around the program's transfers
+    #[account(
+        seeds = [b"mint_authority", mint.key().as_ref()],
+        bump = token_state.mint_authority_bump,
+    )]
+    pub mint_authority: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump = token_state.whitelist_bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: `HolderRecord` PDA of the receiver, created on first receipt
+    #[account(mut)]
+    pub receiver_record: Option<UncheckedAccount<'info>>,
+    /// `HolderRecord` of the sender, closed once its balance reaches zero
+    #[account(mut)]
+    pub sender_record: Option<Account<'info, HolderRecord>>,
+    /// CHECK: Checked against the sender record's `rent_payer`
+    #[account(mut)]
+    pub rent_recipient: Option<UncheckedAccount<'info>>,
+    /// Pays for a new receiver record
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    pub system_program: Option<Program<'info, System>>,
+
+    /// CHECK: `ReferralRecord` PDA of the sender's owner, required once referrals
+    /// are configured; the stats and vault are required when it exists
+    pub sender_referral: Option<UncheckedAccount<'info>>,
+    #[account(mut)]
+    pub referrer_stats: Option<Account<'info, ReferrerStats>>,
+    #[account(mut, token::mint = mint)]
+    pub referral_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Discounts the tax for a sender with a long enough continuous hold
+    #[account(
+        seeds = [b"loyalty_tiers", mint.key().as_ref()],
+        bump = loyalty_tiers.bump,
+    )]
+    pub loyalty_tiers: Option<Account<'info, LoyaltyTiers>>,
+
+    /// CHECK: `BalanceCheckpoints` PDA of the sender, created with `payer` if missing
+    #[account(mut)]
+    pub sender_checkpoints: Option<UncheckedAccount<'info>>,
+    /// CHECK: `BalanceCheckpoints` PDA of the receiver, created with `payer` if missing
+    #[account(mut)]
+    pub receiver_checkpoints: Option<UncheckedAccount<'info>>,
+}
+
+/// How `authority` is allowed to move the sender's tokens
+#[derive(Clone, Copy)]
+pub(crate) enum TransferAuthority {
+    Owner,
+    Delegate,
+}
+
+pub(crate) fn handler(
+    ctx: Context<TransferTokens>,
+    amount: u64,
+    mode: TransferAuthority,
+) -> Result<()> {
+    check_authority(ctx.accounts, mode, amount)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let whitelisted = ctx.accounts.whitelist.contains(&ctx.accounts.sender.owner)
+        || ctx.accounts.whitelist.contains(&ctx.accounts.receiver.owner);
+    ctx.accounts.token_state.check_launch_phase(now, amount, whitelisted)?;
+
+    require!(!ctx.accounts.token_state.is_frozen, ErrorCode::ContractFrozen);
+
+    require!(
+        ctx.accounts.sender.amount >= amount,
+        ErrorCode::InsufficientBalance
+    );
+
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    if let Some(previous_phase) = ctx.accounts.token_state.advance_launch_phase(now) {
+        emit!(LaunchPhaseChangedEvent {
+            mint: ctx.accounts.mint.key(),
+            previous_phase,
+            phase: ctx.accounts.token_state.launch_phase,
+            timestamp: now,
+        });
+    }
+
+    let discount_bps = loyalty_discount(ctx.accounts, now)?;
+
+    // With the transfer fee backend Token-2022 withholds the tax natively
+    let (net_amount, tax_amount, tax_rate_bps) = match ctx.accounts.token_state.tax_backend {
+        TaxBackend::Program => calculate_transfer_amounts(
+            &ctx.accounts.token_state,
+            &ctx.accounts.whitelist,
+            &ctx.accounts.receiver.owner,
+            amount,
+            now,
+            discount_bps,
+        )?,
+        TaxBackend::TransferFee => (amount, 0, 0),
+    };
+
+    let referred = check_referral(
+        &ctx.accounts.token_state,
+        &ctx.accounts.mint.key(),
+        &ctx.accounts.sender.owner,
+        ctx.accounts.sender_referral.as_deref(),
+        ctx.accounts.referrer_stats.as_deref(),
+        ctx.accounts.referral_vault.as_ref().map(|vault| vault.key()).as_ref(),
+    )?;
+    record_checkpoints(ctx.accounts)?;
+
+    let mint_key = ctx.accounts.mint.key();
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"mint_authority",
+        mint_key.as_ref(),
+        &[ctx.bumps.mint_authority],
+    ]];
+    let hook_enabled = ctx.accounts.token_state.transfer_hook_enabled;
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            None,
+        )?;
+    }
+
+    transfer_within_program(
+        &ctx.accounts.sender.to_account_info(),
+        &ctx.accounts.receiver.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        &[],
+        net_amount,
+    )?;
+
+    let split = if referred {
+        tokenomics::split_tax_with_referral(tax_amount, ctx.accounts.token_state.referral_bps)
+    } else {
+        tokenomics::split_tax(tax_amount)
+    };
+    if tax_amount > 0 {
+        allocate_tax(
+            AllocateTaxAccounts {
+                sender: ctx.accounts.sender.clone(),
+                rewards_pool: ctx.accounts.rewards_pool.clone(),
+                lp_fund: ctx.accounts.lp_fund.clone(),
+                referral_vault: ctx.accounts.referral_vault.clone(),
+                sender_authority: ctx.accounts.authority.to_account_info(),
+                mint: ctx.accounts.mint.clone(),
+                token_program: ctx.accounts.token_program.clone(),
+            },
+            &[],
+            &split,
+        )?;
+    }
+
+    if hook_enabled {
+        set_transfer_hook_program(
+            &ctx.accounts.mint,
+            &ctx.accounts.mint_authority,
+            &ctx.accounts.token_program,
+            signer_seeds,
+            Some(crate::ID),
+        )?;
+    }
+
+    ctx.accounts.token_state.total_transactions += 1;
+    ctx.accounts.token_state.record_tax(tax_amount, split.burn)?;
+
+    emit!(TransferEvent {
+        sender: ctx.accounts.sender.key(),
+        receiver: ctx.accounts.receiver.key(),
+        amount: net_amount,
+        timestamp: now,
+        tax_amount,
+        tax_rate_bps,
+    });
+
+    if referred && tax_amount > 0 {
+        let stats = ctx.accounts.referrer_stats
+            .as_mut()
+            .ok_or(ErrorCode::MissingReferralAccounts)?;
+        credit_referrer(
+            stats,
+            &ctx.accounts.sender.owner,
+            tax_amount,
+            split.referral,
+            now,
+        )?;
+    }
+
+    update_holder_records(ctx.accounts, now)?;
+
+    // Update TVL-related balances after transfer
+    if ctx.accounts.receiver.key() == ctx.accounts.lp_pool.key() {
+        ctx.accounts.token_state.update_liquidity_pool(
+            ctx.accounts.lp_pool.amount
+        )?;
+    } else if ctx.accounts.receiver.key() == ctx.accounts.rewards_pool.key() {
+        ctx.accounts.token_state.update_rewards_pool(
+            ctx.accounts.rewards_pool.amount
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Check the signer may move `amount` of the sender's tokens under `mode`
+fn check_authority(accounts: &TransferTokens, mode: TransferAuthority, amount: u64) -> Result<()> {
+    let authority = accounts.authority.key();
+    match mode {
+        TransferAuthority::Owner => {
+            require_keys_eq!(accounts.sender.owner, authority, ErrorCode::Unauthorized);
+        }
+        TransferAuthority::Delegate => {
+            require!(
+                accounts.sender.delegate == COption::Some(authority),
+                ErrorCode::Unauthorized
+            );
+            // The tax is taken from the allowance along with the net amount
+            require!(
+                accounts.sender.delegated_amount >= amount,
+                ErrorCode::InsufficientAllowance
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Loyalty discount earned by the sender's continuous hold
+fn loyalty_discount(accounts: &TransferTokens, now: i64) -> Result<u16> {
+    if accounts.token_state.loyalty_min_balance == 0 {
+        return Ok(0);
+    }
+
+    // Skipping the record would keep a hold the transfer should break
+    let record = accounts.sender_record
+        .as_ref()
+        .ok_or(ErrorCode::MissingHolderAccounts)?;
+    require!(
+        record.mint == accounts.mint.key() && record.token_account == accounts.sender.key(),
+        ErrorCode::AccountMismatch
+    );
+
+    let since = accounts.token_state.loyalty_since;
+    let (Some(tiers), Some(held_for)) = (&accounts.loyalty_tiers, record.held_for(now, since))
+    else {
+        return Ok(0);
+    };
+    Ok(tokenomics::loyalty_discount_bps(
+        &tiers.tiers,
+        accounts.sender.amount,
+        held_for,
+    ))
+}
+
+/// Open the receiver's holder record and close the sender's once it is emptied,
+/// tracking both accounts' continuous hold
+fn update_holder_records(accounts: &mut TransferTokens, now: i64) -> Result<()> {
+    let mint = accounts.mint.key();
+    let threshold = accounts.token_state.loyalty_min_balance;
+
+    if let Some(record) = &accounts.receiver_record {
+        let (Some(payer), Some(system_program)) = (&accounts.payer, &accounts.system_program)
+        else {
+            return err!(ErrorCode::MissingHolderAccounts);
+        };
+        let owner = accounts.receiver.owner;
+        let opened = open_holder_record(
+            &record.to_account_info(),
+            &payer.to_account_info(),
+            &system_program.to_account_info(),
+            &mint,
+            &accounts.receiver.key(),
+            &owner,
+            now,
+        )?;
+        if opened {
+            let token_state = &mut accounts.token_state;
+            token_state.holder_count = token_state.holder_count
+                .checked_add(1)
+                .ok_or(ErrorCode::ArithmeticOverflow)?;
+            emit!(HolderEvent {
+                mint,
+                owner,
+                is_added: true,
+                holder_count: token_state.holder_count,
+                timestamp: now,
+            });
+        }
+
+        accounts.receiver.reload()?;
+        let mut data = record.try_borrow_mut_data()?;
+        let mut holder = HolderRecord::try_deserialize(&mut &data[..])?;
+        holder.track_hold(accounts.receiver.amount, threshold, now);
+        holder.try_serialize(&mut &mut data[..])?;
+    }
+
+    if let Some(record) = &mut accounts.sender_record {
+        let owner = accounts.sender.owner;
+        require!(
+            record.mint == mint && record.token_account == accounts.sender.key(),
+            ErrorCode::AccountMismatch
+        );
+        accounts.sender.reload()?;
+        if accounts.sender.amount == 0 {
+            let rent_recipient = accounts.rent_recipient
+                .as_ref()
+                .ok_or(ErrorCode::MissingHolderAccounts)?;
+            close_holder_record(record, &rent_recipient.to_account_info())?;
+
+            let token_state = &mut accounts.token_state;
+            token_state.holder_count = token_state.holder_count
+                .checked_sub(1)
+                .ok_or(ErrorCode::ArithmeticUnderflow)?;
+            emit!(HolderEvent {
+                mint,
+                owner,
+                is_added: false,
+                holder_count: token_state.holder_count,
+                timestamp: now,
+            });
+        } else {
+            record.track_hold(accounts.sender.amount, threshold, now);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checkpoint both balances before the transfer changes them, once a snapshot exists
+fn record_checkpoints(accounts: &TransferTokens) -> Result<()> {
+    let payer = accounts.payer.as_ref().map(|payer| payer.to_account_info());
+    let system_program = accounts.system_program
+        .as_ref()
+        .map(|program| program.to_account_info());
+    for (checkpoints, token_account) in [
+        (&accounts.sender_checkpoints, &accounts.sender),
+        (&accounts.receiver_checkpoints, &accounts.receiver),
+    ] {
+        record_checkpoint(
+            &accounts.token_state,
+            checkpoints.as_deref(),
+            token_account,
+            payer.as_ref(),
+            system_program.as_ref(),
+        )?;
+    }
+    Ok(())
+}
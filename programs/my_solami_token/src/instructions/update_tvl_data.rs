@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::state::TokenState;
+
+#[derive(Accounts)]
+pub struct UpdateTVL<'info> {
+    #[account(
+        mut,
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+        has_one = lp_pool,
+        has_one = rewards_pool,
+        has_one = staking_pool,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(token::mint = mint)]
+    pub lp_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub rewards_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(token::mint = mint)]
+    pub staking_pool: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn handler(ctx: Context<UpdateTVL>) -> Result<()> {
+    require!(
+        ctx.accounts.admin.key() == ctx.accounts.token_state.admin,
+        ErrorCode::Unauthorized
+    );
+
+    ctx.accounts.token_state.update_liquidity_pool(
+        ctx.accounts.lp_pool.amount
+    )?;
+    ctx.accounts.token_state.update_rewards_pool(
+        ctx.accounts.rewards_pool.amount
+    )?;
+    ctx.accounts.token_state.update_staking_pool(
+        ctx.accounts.staking_pool.amount
+    )?;
+
+    Ok(())
+}
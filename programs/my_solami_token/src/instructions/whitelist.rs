@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::events::WhitelistEvent;
+use crate::state::{TokenState, Whitelist};
+
+#[derive(Accounts)]
+pub struct WhitelistOperation<'info> {
+    #[account(
+        seeds = [b"token_state", mint.key().as_ref()],
+        bump = token_state.bump,
+    )]
+    pub token_state: Account<'info, TokenState>,
+    #[account(mut, address = token_state.admin)]
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"whitelist", mint.key().as_ref()],
+        bump = token_state.whitelist_bump,
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+    pub mint: InterfaceAccount<'info, Mint>,
+}
+
+pub(crate) fn add_to_whitelist(
+    ctx: Context<WhitelistOperation>,
+    wallet: Pubkey,
+) -> Result<()> {
+    ctx.accounts.whitelist.add(wallet)?;
+
+    emit!(WhitelistEvent {
+        wallet,
+        is_added: true,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn remove_from_whitelist(
+    ctx: Context<WhitelistOperation>,
+    wallet: Pubkey,
+) -> Result<()> {
+    ctx.accounts.whitelist.remove(wallet)?;
+
+    emit!(WhitelistEvent {
+        wallet,
+        is_added: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
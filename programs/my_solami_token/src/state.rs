@@ -1,9 +1,36 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::tokenomics;
+
+/// Maximum number of wallets the whitelist account has room for
+pub const MAX_WHITELIST_WALLETS: usize = 15;
+
+/// Maximum number of loyalty tiers
+pub const MAX_LOYALTY_TIERS: usize = 4;
+
+/// Maximum number of recipients in one `batch_transfer`; each adds three accounts
+/// to the 22 a full batch transaction locks otherwise (the named accounts, the
+/// sender's PDAs, the program, a separate fee payer and the compute budget
+/// program), which must stay within the runtime's 64 account locks
+pub const MAX_BATCH_TRANSFERS: usize = 14;
+
+/// Maximum number of checkpoints kept per token account; older ones are pruned
+pub const MAX_CHECKPOINTS: usize = 32;
+
+/// Seconds after `initialize_token` at which the default schedule opens trading
+pub const DEFAULT_TRADING_DELAY: i64 = 300;
 
 #[account]
+#[derive(InitSpace)]
 pub struct TokenState {
     pub admin: Pubkey,
+    /// Pools the tax and initial supply are paid into, bound at `initialize_token`
+    pub lp_pool: Pubkey,
+    pub rewards_pool: Pubkey,
+    pub lp_fund: Pubkey,
+    pub staking_pool: Pubkey,
+    pub swap_wallet: Pubkey,
     pub total_supply: u64,
     pub launch_time: i64,
     pub reward_distribution_start_time: i64,
@@ -16,14 +43,55 @@ pub struct TokenState {
     pub liquidity_pool_balance: u64,
     pub staking_pool_balance: u64,
     pub rewards_pool_balance: u64,
+    pub supply_finalized: bool,
+    pub max_supply: u64,
+    pub emission_per_epoch: u64,
+    pub emission_epoch_duration: i64,
+    pub last_emission_time: i64,
+    pub total_emitted: u64,
+    pub emission_recipient: Pubkey,
+    pub transfer_hook_enabled: bool,
+    pub tax_backend: TaxBackend,
+    /// Wallets with an open `HolderRecord`
+    pub holder_count: u64,
+    /// Phase last observed on chain, used to emit `LaunchPhaseChangedEvent`
+    pub launch_phase: LaunchPhase,
+    /// Admin-set start of each launch phase
+    pub whitelist_phase_start: i64,
+    pub limited_phase_start: i64,
+    pub public_phase_start: i64,
+    /// Largest transfer allowed during the limited phase
+    pub limited_max_transfer: u64,
+    /// Anti-sniper tax starting at `launch_tax_bps` when trading opens at
+    /// `whitelist_phase_start` and decaying to the base rate over `launch_tax_window` seconds
+    pub launch_tax_bps: u16,
+    pub launch_tax_window: i64,
+    /// 0 decays linearly, otherwise in this many equal steps
+    pub launch_tax_steps: u8,
+    /// Token account holding the presale tranche escrowed at `initialize_token`
+    pub presale_escrow: Pubkey,
+    pub presale_tranche: u64,
+    /// Token account holding the bonding curve reserve, default when launching into `lp_pool`
+    pub curve_reserve: Pubkey,
+    /// Token account owned by the `mint_authority` PDA holding unclaimed referral rewards
+    pub referral_vault: Pubkey,
+    /// Share of the rewards tax credited to a referred sender's referrer, in basis points
+    pub referral_bps: u16,
+    /// Smallest loyalty tier balance; holding less breaks a continuous hold. 0 without tiers
+    pub loyalty_min_balance: u64,
+    /// When the loyalty tiers were last configured; holds only count from then
+    pub loyalty_since: i64,
+    /// Id of the latest `Snapshot`, 0 before the first
+    pub snapshot_id: u64,
+    /// Canonical bumps of the PDAs seeded by the mint
+    pub bump: u8,
+    pub whitelist_bump: u8,
+    pub mint_authority_bump: u8,
 }
 
 impl TokenState {
-    pub const SIZE: usize = 8 + // discriminator
-        32 + // admin pubkey
-        (8 * 8) + // existing u64 fields
-        1 + // bool
-        (8 * 3); // new TVL-related fields
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
 
     pub fn initialize(
         &mut self,
@@ -34,7 +102,7 @@ impl TokenState {
         self.admin = admin;
         self.total_supply = supply;
         self.launch_time = launch_time;
-        self.reward_distribution_start_time = launch_time + 2520;
+        self.reward_distribution_start_time = launch_time + 2520; // 42 minutes
         self.total_transactions = 0;
         self.total_tax_collected = 0;
         self.total_burned = 0;
@@ -44,6 +112,44 @@ impl TokenState {
         self.liquidity_pool_balance = 0;
         self.staking_pool_balance = 0;
         self.rewards_pool_balance = 0;
+        self.supply_finalized = false;
+        self.max_supply = supply;
+        self.emission_per_epoch = 0;
+        self.emission_epoch_duration = 0;
+        self.last_emission_time = launch_time;
+        self.total_emitted = 0;
+        self.emission_recipient = Pubkey::default();
+        self.transfer_hook_enabled = false;
+        self.tax_backend = TaxBackend::Program;
+        self.holder_count = 0;
+        // Trading opens to everyone at once, as it did before phases existed
+        self.launch_phase = LaunchPhase::PreLaunch;
+        self.whitelist_phase_start = launch_time + DEFAULT_TRADING_DELAY;
+        self.limited_phase_start = launch_time + DEFAULT_TRADING_DELAY;
+        self.public_phase_start = launch_time + DEFAULT_TRADING_DELAY;
+        self.limited_max_transfer = supply;
+        self.launch_tax_bps = tokenomics::BASE_TAX_BPS;
+        self.launch_tax_window = 0;
+        self.launch_tax_steps = 0;
+        self.presale_escrow = Pubkey::default();
+        self.presale_tranche = 0;
+        self.curve_reserve = Pubkey::default();
+        self.referral_vault = Pubkey::default();
+        self.referral_bps = 0;
+        self.loyalty_min_balance = 0;
+        self.loyalty_since = 0;
+        self.snapshot_id = 0;
+    }
+
+    /// Tax rate in basis points for a taxed transfer at `now`, decaying from
+    /// when trading opens so a delayed launch keeps its full window
+    pub fn tax_rate_bps(&self, now: i64) -> u16 {
+        tokenomics::launch_tax_bps(
+            self.launch_tax_bps,
+            self.launch_tax_window,
+            self.launch_tax_steps,
+            now - self.whitelist_phase_start,
+        )
     }
 
     pub fn update_liquidity_pool(&mut self, new_balance: u64) -> Result<()> {
@@ -61,6 +167,90 @@ impl TokenState {
         Ok(())
     }
 
+    /// Record a collected tax and the part of it that was burned
+    pub fn record_tax(&mut self, tax_amount: u64, burned: u64) -> Result<()> {
+        self.total_tax_collected = self.total_tax_collected
+            .checked_add(tax_amount)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_burned = self.total_burned
+            .checked_add(burned)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        self.total_supply = self.total_supply
+            .checked_sub(burned)
+            .ok_or(ErrorCode::ArithmeticUnderflow)?;
+        Ok(())
+    }
+
+    /// Launch phase in effect at `now`
+    pub fn launch_phase_at(&self, now: i64) -> LaunchPhase {
+        if now >= self.public_phase_start {
+            LaunchPhase::Public
+        } else if now >= self.limited_phase_start {
+            LaunchPhase::Limited
+        } else if now >= self.whitelist_phase_start {
+            LaunchPhase::WhitelistOnly
+        } else {
+            LaunchPhase::PreLaunch
+        }
+    }
+
+    /// Check a transfer against the launch phase; `whitelisted` if either side is
+    pub fn check_launch_phase(&self, now: i64, amount: u64, whitelisted: bool) -> Result<()> {
+        match self.launch_phase_at(now) {
+            LaunchPhase::PreLaunch => err!(ErrorCode::TradingNotEnabled),
+            LaunchPhase::WhitelistOnly => {
+                require!(whitelisted, ErrorCode::WhitelistOnlyPhase);
+                Ok(())
+            }
+            LaunchPhase::Limited => {
+                require!(
+                    amount <= self.limited_max_transfer,
+                    ErrorCode::TransferLimitExceeded
+                );
+                Ok(())
+            }
+            LaunchPhase::Public => Ok(()),
+        }
+    }
+
+    /// Record the phase in effect at `now`, returning the previous one if it changed
+    pub fn advance_launch_phase(&mut self, now: i64) -> Option<LaunchPhase> {
+        let phase = self.launch_phase_at(now);
+        if phase == self.launch_phase {
+            return None;
+        }
+        Some(std::mem::replace(&mut self.launch_phase, phase))
+    }
+
+    /// Replace the address stored for `pool`, returning the previous one
+    pub fn set_pool(&mut self, pool: PoolKind, address: Pubkey) -> Pubkey {
+        let slot = match pool {
+            PoolKind::LpPool => &mut self.lp_pool,
+            PoolKind::RewardsPool => &mut self.rewards_pool,
+            PoolKind::LpFund => &mut self.lp_fund,
+            PoolKind::StakingPool => &mut self.staking_pool,
+            PoolKind::SwapWallet => &mut self.swap_wallet,
+        };
+        std::mem::replace(slot, address)
+    }
+
+    /// Whether `token_account` is one of the pools, reserves or vaults this program
+    /// pays into; their balances move without checkpoints, so snapshots skip them
+    pub fn is_pool(&self, token_account: &Pubkey) -> bool {
+        [
+            self.lp_pool,
+            self.rewards_pool,
+            self.lp_fund,
+            self.staking_pool,
+            self.swap_wallet,
+            self.emission_recipient,
+            self.presale_escrow,
+            self.curve_reserve,
+            self.referral_vault,
+        ]
+        .contains(token_account)
+    }
+
     pub fn get_total_tvl(&self) -> u64 {
         self.liquidity_pool_balance
             .checked_add(self.staking_pool_balance)
@@ -69,15 +259,88 @@ impl TokenState {
     }
 }
 
+/// Where the transfer tax is computed and collected
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaxBackend {
+    /// `transfer_tokens` computes the tax and splits it with CPIs
+    Program,
+    /// Token-2022 withholds the tax; `harvest_and_split_fees` splits it later
+    TransferFee,
+}
+
+/// Trading stage of the launch, advancing with the schedule on `TokenState`
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LaunchPhase {
+    /// Nobody can trade
+    PreLaunch,
+    /// Only transfers from or to whitelisted wallets
+    WhitelistOnly,
+    /// Everyone, up to `limited_max_transfer` per transfer
+    Limited,
+    /// No launch restrictions
+    Public,
+}
+
+/// Token accounts whose address is stored on `TokenState`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolKind {
+    LpPool,
+    RewardsPool,
+    LpFund,
+    StakingPool,
+    SwapWallet,
+}
+
+/// How the bonding curve price grows every `step_size` tokens sold
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveKind {
+    /// Price rises by `growth` each step
+    Linear,
+    /// Price rises by `growth` basis points each step
+    Exponential,
+}
+
+/// Stepped bonding curve; prices are lamports per `CURVE_PRICE_SCALE` token base units
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurveShape {
+    pub kind: CurveKind,
+    pub start_price: u64,
+    pub growth: u64,
+    pub step_size: u64,
+}
+
+/// Tax discount for holders of at least `min_balance` for `min_hold` seconds
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoyaltyTier {
+    pub min_hold: i64,
+    pub min_balance: u64,
+    /// Share of the tax rate waived, in basis points
+    pub discount_bps: u16,
+}
+
+/// One recipient token account and the gross amount sent to it by `batch_transfer`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchTransferItem {
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct TransferFeeParams {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
 #[account]
+#[derive(InitSpace)]
 pub struct Whitelist {
+    #[max_len(MAX_WHITELIST_WALLETS)]
     pub wallets: Vec<Pubkey>,
 }
 
 impl Whitelist {
-    pub const SIZE: usize = 8 + // discriminator
-        4 + // vec length
-        (32 * 100); // max 100 wallets * pubkey size
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
 
     pub fn initialize(&mut self, wallets: Vec<Pubkey>) {
         self.wallets = wallets;
@@ -86,251 +349,371 @@ impl Whitelist {
     pub fn contains(&self, wallet: &Pubkey) -> bool {
         self.wallets.contains(wallet)
     }
+
+    /// Add a wallet, failing if it is already whitelisted or the account is full
+    pub fn add(&mut self, wallet: Pubkey) -> Result<()> {
+        require!(!self.contains(&wallet), ErrorCode::AlreadyWhitelisted);
+        require!(
+            self.wallets.len() < MAX_WHITELIST_WALLETS,
+            ErrorCode::WhitelistFull
+        );
+        self.wallets.push(wallet);
+        Ok(())
+    }
+
+    /// Remove a wallet, failing if it is not whitelisted
+    pub fn remove(&mut self, wallet: Pubkey) -> Result<()> {
+        let index = self.wallets.iter()
+            .position(|&w| w == wallet)
+            .ok_or(ErrorCode::NotInWhitelist)?;
+        self.wallets.remove(index);
+        Ok(())
+    }
 }
 
-// Account Validation Structures
-#[derive(Accounts)]
-pub struct InitializeToken<'info> {
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + TokenState::SIZE,
-        seeds = [b"token_state", mint.key().as_ref()],
-        bump,
-    )]
-    pub token_state: Account<'info, TokenState>,
-
-    #[account(
-        init,
-        payer = admin,
-        space = 8 + Whitelist::SIZE,
-        seeds = [b"whitelist", mint.key().as_ref()],
-        bump,
-    )]
-    pub whitelist: Account<'info, Whitelist>,
-
-    /// CHECK: This is the PDA that will be the mint authority
-    #[account(
-        seeds = [b"mint_authority", mint.key().as_ref()],
-        bump,
-    )]
-    pub mint_authority: AccountInfo<'info>,
-
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    #[account(mut)]
-    pub admin_token_account: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub rewards_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_fund: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub token_account: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
-
-#[derive(Accounts)]
-pub struct TransferTokens<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut)]
-    pub sender: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub receiver: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub rewards_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_fund: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_pool: Account<'info, TokenAccount>,
-    #[account(
-        seeds = [b"mint_authority", mint.key().as_ref()],
-        bump,
-    )]
-    pub sender_authority: AccountInfo<'info>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub whitelist: Account<'info, Whitelist>,
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct ManualBurn<'info> {
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    #[account(mut)]
-    pub admin_token_account: Account<'info, TokenAccount>,
-    #[account(mut, address = token_state.admin)]
-    pub admin: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-}
-
-#[derive(Accounts)]
-pub struct SwapRewards<'info> {
-    #[account(mut)]
-    pub rewards_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub swap_wallet: Account<'info, TokenAccount>,
-    #[account(mut, address = token_state.admin)]
-    pub admin: Signer<'info>,
-    pub token_program: Program<'info, Token>,
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-}
-
-#[derive(Accounts)]
-pub struct WhitelistOperation<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut, address = token_state.admin)]
-    pub admin: Signer<'info>,
-    #[account(mut)]
-    pub whitelist: Account<'info, Whitelist>,
-}
-
-#[derive(Accounts)]
-pub struct TransferOwnership<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut, address = token_state.admin)]
-    pub admin: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct UserBurn<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut)]
-    pub user_token_account: Account<'info, TokenAccount>,
-    pub user: Signer<'info>,
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-}
-
-#[derive(Accounts)]
-pub struct FreezeContract<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut, address = token_state.admin)]
-    pub admin: Signer<'info>,
-}
-
-#[derive(Accounts)]
-pub struct UpdateTVL<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut)]
-    pub lp_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub rewards_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub staking_pool: Account<'info, TokenAccount>,
-    #[account(mut, address = token_state.admin)]
-    pub admin: Signer<'info>,
-}
-
-#[derive(Accounts, Clone)]
-pub struct AllocateTaxAccounts<'info> {
-    #[account(mut)]
-    pub token_state: Account<'info, TokenState>,
-    #[account(mut)]
-    pub sender: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub rewards_pool: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub lp_fund: Account<'info, TokenAccount>,
-
-    /// CHECK: This is the PDA that signs the tax allocation, validated by the program
-    #[account(
-        seeds = [b"mint_authority", mint.key().as_ref()],
-        bump,
-    )]
-    pub sender_authority: AccountInfo<'info>,
-
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-}
-
-// Events
-#[event]
-pub struct SwapEvent {
-    pub amount: u64,
-    pub timestamp: i64,
-    pub pool: Pubkey,
+/// Registry entry for a token account holding the token, seeded by mint and
+/// token account so an owner's other accounts never close it
+#[account]
+#[derive(InitSpace)]
+pub struct HolderRecord {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    /// When the account first received tokens through the program
+    pub first_seen: i64,
+    /// Start of the current hold at or above `TokenState::loyalty_min_balance`
+    pub hold_start: Option<i64>,
+    /// Refunded the rent once the record is closed
+    pub rent_payer: Pubkey,
+    pub bump: u8,
 }
 
-#[event]
-pub struct TransferEvent {
-    pub sender: Pubkey,
-    pub receiver: Pubkey,
-    pub amount: u64,
-    pub timestamp: i64,
-    pub tax_amount: u64,
+impl HolderRecord {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+
+    pub fn find_address(mint: &Pubkey, token_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"holder", mint.as_ref(), token_account.as_ref()],
+            &crate::ID,
+        )
+    }
+
+    /// Start or break the continuous hold once the account's balance is `balance`
+    pub fn track_hold(&mut self, balance: u64, threshold: u64, now: i64) {
+        if balance < threshold {
+            self.hold_start = None;
+        } else if self.hold_start.is_none() {
+            self.hold_start = Some(now);
+        }
+    }
+
+    /// Seconds of continuous hold at `now`, counted from `since` at the earliest
+    pub fn held_for(&self, now: i64, since: i64) -> Option<i64> {
+        self.hold_start.map(|start| now - start.max(since))
+    }
 }
 
-#[event]
-pub struct BurnEvent {
-    pub burner: Pubkey,
-    pub amount: u64,
-    pub timestamp: i64,
-    pub new_total_supply: u64,
+/// Balance a token account held when snapshot `snapshot_id` was taken
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub snapshot_id: u64,
+    pub balance: u64,
 }
 
-#[event]
-pub struct WhitelistEvent {
-    pub wallet: Pubkey,
-    pub is_added: bool,
+/// Admin instruction a governance proposal executes once passed
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    ConfigureLaunchTax { start_bps: u16, window: i64, steps: u8 },
+    ConfigureReferrals { referral_bps: u16 },
+    AddToWhitelist { wallet: Pubkey },
+    RemoveFromWhitelist { wallet: Pubkey },
+    FreezeContract { freeze: bool },
+}
+
+/// Presale terms and totals; the account's own lamports are the SOL vault
+#[account]
+#[derive(InitSpace)]
+pub struct Presale {
+    pub mint: Pubkey,
+    /// Token base units bought per SOL contributed
+    pub tokens_per_sol: u64,
+    /// Caps in lamports; refunds open if `soft_cap` isn't raised by `end_time`
+    pub soft_cap: u64,
+    pub hard_cap: u64,
+    pub wallet_cap: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_raised: u64,
+    pub contributors: u64,
+    /// Set once the admin has taken the proceeds or reclaimed the tranche
+    pub finalized: bool,
+    pub bump: u8,
+}
+
+impl Presale {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+
+    pub fn soft_cap_met(&self) -> bool {
+        self.total_raised >= self.soft_cap
+    }
+}
+
+/// Bonding curve launch state; the account's own lamports are the SOL reserve
+#[account]
+#[derive(InitSpace)]
+pub struct BondingCurve {
+    pub mint: Pubkey,
+    pub shape: CurveShape,
+    /// Tokens the reserve held when the curve was configured
+    pub curve_supply: u64,
+    pub tokens_sold: u64,
+    /// Lamports paid in by buyers and not yet paid out
+    pub sol_reserve: u64,
+    /// Market cap in lamports at which the reserve migrates to `lp_pool`
+    /// and the owner of `lp_pool`
+    pub migration_market_cap: u64,
+    pub migrated: bool,
+    pub bump: u8,
+}
+
+impl BondingCurve {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+/// One wallet's presale contribution, closed on claim or refund
+#[account]
+#[derive(InitSpace)]
+pub struct PresaleReceipt {
+    pub presale: Pubkey,
+    pub contributor: Pubkey,
+    /// Lamports contributed
+    pub contributed: u64,
+    pub bump: u8,
+}
+
+impl PresaleReceipt {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ConfidentialConfig {
+    pub mint: Pubkey,
+    /// All zeroes when no auditor is set
+    pub auditor_elgamal_pubkey: [u8; 32],
+    pub bump: u8,
+}
+
+impl ConfidentialConfig {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+
+    pub fn auditor(&self) -> Option<[u8; 32]> {
+        if self.auditor_elgamal_pubkey == [0; 32] {
+            None
+        } else {
+            Some(self.auditor_elgamal_pubkey)
+        }
+    }
+}
+
+/// Referrer a wallet registered, seeded by mint and the referred wallet
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralRecord {
+    pub mint: Pubkey,
+    pub user: Pubkey,
+    pub referrer: Pubkey,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl ReferralRecord {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+
+    pub fn find_address(mint: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"referral", mint.as_ref(), user.as_ref()], &crate::ID)
+    }
+}
+
+/// Referral stats and claimable rewards, seeded by mint and referrer
+#[account]
+#[derive(InitSpace)]
+pub struct ReferrerStats {
+    pub mint: Pubkey,
+    pub referrer: Pubkey,
+    pub referred_count: u64,
+    /// Tax paid by referred wallets through `transfer_tokens` and `batch_transfer`
+    pub referred_tax: u64,
+    pub total_earned: u64,
+    /// Credited but not yet claimed from the referral vault
+    pub claimable: u64,
+    pub bump: u8,
+}
+
+impl ReferrerStats {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Loyalty tier definitions, seeded by the mint
+#[account]
+#[derive(InitSpace)]
+pub struct LoyaltyTiers {
+    #[max_len(MAX_LOYALTY_TIERS)]
+    pub tiers: Vec<LoyaltyTier>,
+    pub bump: u8,
+}
+
+impl LoyaltyTiers {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Governance settings, seeded by the mint; also the PDA that signs passed
+/// proposals once set as `TokenState::admin`
+#[account]
+#[derive(InitSpace)]
+pub struct Governance {
+    pub mint: Pubkey,
+    /// Seconds a proposal stays open for votes
+    pub voting_period: i64,
+    /// Minimum votes cast, for and against, for a proposal to pass
+    pub quorum: u64,
+    /// Minimum balance needed to create a proposal
+    pub proposal_threshold: u64,
+    pub proposal_count: u64,
+    pub bump: u8,
+}
+
+impl Governance {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Governance proposal, seeded by mint and its sequential id
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub mint: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    /// Snapshot taken with the proposal; votes weigh balances at it
+    pub snapshot_id: u64,
+    pub voting_ends: i64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl Proposal {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+
+    /// Whether voting has closed with quorum and a majority in favour
+    pub fn passed(&self, quorum: u64, now: i64) -> bool {
+        let votes = self.yes_votes as u128 + self.no_votes as u128;
+        now >= self.voting_ends && votes >= quorum as u128 && self.yes_votes > self.no_votes
+    }
+}
+
+/// A ballot on a proposal, seeded by proposal and the token account it votes
+/// with; prevents voting its balance twice
+#[account]
+#[derive(InitSpace)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub token_account: Pubkey,
+    pub approve: bool,
+    /// Balance of `token_account` at the proposal's snapshot
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Snapshot recorded by `take_snapshot` or `create_proposal`, seeded by mint and id
+#[account]
+#[derive(InitSpace)]
+pub struct Snapshot {
+    pub mint: Pubkey,
+    pub id: u64,
+    pub slot: u64,
     pub timestamp: i64,
+    pub bump: u8,
+}
+
+impl Snapshot {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+}
+
+/// Balances of a token account at the snapshots after which an instruction or
+/// a direct transfer through the hook changed it, seeded by the token account
+#[account]
+#[derive(InitSpace)]
+pub struct BalanceCheckpoints {
+    pub token_account: Pubkey,
+    /// Ascending by snapshot id
+    #[max_len(MAX_CHECKPOINTS)]
+    pub checkpoints: Vec<Checkpoint>,
+    /// Latest snapshot id whose checkpoint was pruned, 0 if none
+    pub pruned_through: u64,
+    /// `TokenState::snapshot_id` when the account was opened; every change since
+    /// is recorded, so only balances at later snapshots are proven
+    pub opened_at: u64,
+    pub bump: u8,
 }
 
-// Error Codes
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid whitelist size")]
-    InvalidWhitelistSize,
-    #[msg("Insufficient balance for operation")]
-    InsufficientBalance,
-    #[msg("Trading not enabled yet")]
-    TradingNotEnabled,
-    #[msg("Whitelist is full")]
-    WhitelistFull,
-    #[msg("Address not in whitelist")]
-    NotInWhitelist,
-    #[msg("Unauthorized action")]
-    Unauthorized,
-    #[msg("Transfer cooldown active")]
-    TransferCooldown,
-    #[msg("Transfer amount exceeds limit")]
-    TransferLimitExceeded,
-    #[msg("Invalid new admin address")]
-    InvalidAdminAddress,
-    #[msg("Invalid supply")]
-    InvalidSupply,
-    #[msg("Invalid amount")]
-    InvalidAmount,
-    #[msg("Contract is frozen")]
-    ContractFrozen,
-    #[msg("Arithmetic overflow")]
-    ArithmeticOverflow,
-    #[msg("Arithmetic underflow")]
-    ArithmeticUnderflow,
-    #[msg("Required account not found")]
-    AccountNotFound,
-    #[msg("Insufficient accounts provided")]
-    InsufficientAccounts,
-    #[msg("Account mismatch")]
-    AccountMismatch,
+impl BalanceCheckpoints {
+    /// Account size without the discriminator
+    pub const SIZE: usize = Self::INIT_SPACE;
+
+    pub fn find_address(token_account: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"checkpoints", token_account.as_ref()], &crate::ID)
+    }
+
+    /// Record `balance`, about to change, as the balance at `snapshot_id` unless
+    /// a checkpoint for it exists
+    pub fn record(&mut self, snapshot_id: u64, balance: u64) {
+        let recorded = self
+            .checkpoints
+            .last()
+            .is_some_and(|checkpoint| checkpoint.snapshot_id >= snapshot_id);
+        if snapshot_id == 0 || recorded {
+            return;
+        }
+        if self.checkpoints.len() == MAX_CHECKPOINTS {
+            self.pruned_through = self.checkpoints.remove(0).snapshot_id;
+        }
+        self.checkpoints.push(Checkpoint {
+            snapshot_id,
+            balance,
+        });
+    }
+
+    /// Balance at `snapshot_id` given the current balance; `None` once pruned
+    pub fn balance_at(&self, snapshot_id: u64, current: u64) -> Option<u64> {
+        if snapshot_id <= self.pruned_through {
+            return None;
+        }
+        // The first change after the snapshot recorded what it held until then
+        Some(
+            self.checkpoints
+                .iter()
+                .find(|checkpoint| checkpoint.snapshot_id >= snapshot_id)
+                .map_or(current, |checkpoint| checkpoint.balance),
+        )
+    }
 }
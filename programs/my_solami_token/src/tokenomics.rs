@@ -0,0 +1,207 @@
+//! Pure tax and distribution arithmetic, kept free of accounts so it can be
+//! tested on the host.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+
+use crate::errors::ErrorCode;
+use crate::state::{CurveKind, CurveShape, LoyaltyTier};
+
+/// Tax taken from transfers to non-whitelisted receivers, in percent
+pub const TAX_PERCENT: u64 = 10;
+/// `TAX_PERCENT` in basis points, the rate the launch tax decays to
+pub const BASE_TAX_BPS: u16 = (TAX_PERCENT * 100) as u16;
+/// Share of the tax sent to the rewards pool, in tenths
+pub const REWARDS_TENTHS: u64 = 7;
+/// Share of the tax sent to the LP fund, in tenths
+pub const LP_FUND_TENTHS: u64 = 2;
+
+/// Initial supply shares, in percent
+pub const LP_PERCENT: u64 = 40;
+pub const BURN_ALLOCATION_PERCENT: u64 = 30;
+pub const WHITELIST_PERCENT: u64 = 15;
+pub const MARKETING_PERCENT: u64 = 15;
+
+/// How a collected tax is split between pools and burn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaxSplit {
+    pub rewards: u64,
+    pub lp_fund: u64,
+    pub burn: u64,
+    /// Carved out of the rewards share for the payer's referrer
+    pub referral: u64,
+}
+
+/// Initial supply allocation minted by `initialize_token`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupplyAllocation {
+    pub lp: u64,
+    pub burn_allocation: u64,
+    pub marketing: u64,
+    pub whitelist_per_wallet: u64,
+    /// Escrowed for presale buyers, carved out of the marketing share
+    pub presale: u64,
+}
+
+impl SupplyAllocation {
+    /// Total minted for `wallets` whitelisted wallets
+    pub fn minted(&self, wallets: u64) -> u64 {
+        // Every term is a share of a u64 supply, so the sum fits
+        self.lp
+            + self.burn_allocation
+            + self.marketing
+            + self.presale
+            + self.whitelist_per_wallet * wallets
+    }
+
+    /// Move `tranche` tokens from the marketing share into the presale escrow
+    pub fn with_presale(mut self, tranche: u64) -> Result<Self> {
+        require!(tranche <= self.marketing, ErrorCode::InvalidPresale);
+        self.marketing -= tranche;
+        self.presale = tranche;
+        Ok(self)
+    }
+}
+
+/// `amount * numerator / denominator` without intermediate overflow
+pub fn mul_div(amount: u64, numerator: u64, denominator: u64) -> u64 {
+    (amount as u128 * numerator as u128 / denominator as u128) as u64
+}
+
+/// Split a transfer into (net, tax)
+pub fn transfer_amounts(amount: u64, is_whitelisted: bool) -> (u64, u64) {
+    transfer_amounts_at(amount, if is_whitelisted { 0 } else { BASE_TAX_BPS })
+}
+
+/// Split a transfer into (net, tax) at `tax_bps` basis points
+pub fn transfer_amounts_at(amount: u64, tax_bps: u16) -> (u64, u64) {
+    let tax = mul_div(amount, tax_bps as u64, 10_000);
+    (amount - tax, tax)
+}
+
+/// Launch tax `elapsed` seconds after trading opens, decaying from `start_bps` to
+/// `BASE_TAX_BPS` over `window` seconds; `steps == 0` decays linearly
+pub fn launch_tax_bps(start_bps: u16, window: i64, steps: u8, elapsed: i64) -> u16 {
+    if start_bps <= BASE_TAX_BPS || window <= 0 || elapsed >= window {
+        return BASE_TAX_BPS;
+    }
+    let elapsed = elapsed.max(0) as u128;
+    let window = window as u128;
+    let excess = (start_bps - BASE_TAX_BPS) as u128;
+    let decayed = if steps == 0 {
+        excess * elapsed / window
+    } else {
+        let steps = steps as u128;
+        excess * (elapsed * steps / window) / steps
+    };
+    // `decayed` is below `excess`, which fits in a u16
+    start_bps - decayed as u16
+}
+
+/// Largest discount in `tiers` for a holder of `balance` held for `held_for` seconds
+pub fn loyalty_discount_bps(tiers: &[LoyaltyTier], balance: u64, held_for: i64) -> u16 {
+    tiers
+        .iter()
+        .filter(|tier| balance >= tier.min_balance && held_for >= tier.min_hold)
+        .map(|tier| tier.discount_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+/// `tax_bps` less `discount_bps` of itself
+pub fn discounted_tax_bps(tax_bps: u16, discount_bps: u16) -> u16 {
+    // The result is at most `tax_bps`, which fits in a u16
+    mul_div(tax_bps as u64, 10_000 - discount_bps.min(10_000) as u64, 10_000) as u16
+}
+
+/// Split collected tax into rewards, LP fund and burn; rounding dust is burned
+pub fn split_tax(tax: u64) -> TaxSplit {
+    let rewards = mul_div(tax, REWARDS_TENTHS, 10);
+    let lp_fund = mul_div(tax, LP_FUND_TENTHS, 10);
+    TaxSplit {
+        rewards,
+        lp_fund,
+        burn: tax - rewards - lp_fund,
+        referral: 0,
+    }
+}
+
+/// [`split_tax`] with `referral_bps` of the rewards share credited to a referrer
+pub fn split_tax_with_referral(tax: u64, referral_bps: u16) -> TaxSplit {
+    let split = split_tax(tax);
+    let referral = mul_div(split.rewards, referral_bps as u64, 10_000);
+    TaxSplit {
+        rewards: split.rewards - referral,
+        referral,
+        ..split
+    }
+}
+
+/// Initial supply allocation; rounding dust is left unminted
+pub fn initial_allocation(total_supply: u64, whitelist_wallets: usize) -> Result<SupplyAllocation> {
+    require!(total_supply > 0, ErrorCode::InvalidSupply);
+    require!(whitelist_wallets > 0, ErrorCode::InvalidWhitelistSize);
+
+    Ok(SupplyAllocation {
+        lp: mul_div(total_supply, LP_PERCENT, 100),
+        burn_allocation: mul_div(total_supply, BURN_ALLOCATION_PERCENT, 100),
+        marketing: mul_div(total_supply, MARKETING_PERCENT, 100),
+        whitelist_per_wallet: mul_div(total_supply, WHITELIST_PERCENT, 100)
+            / whitelist_wallets as u64,
+        presale: 0,
+    })
+}
+
+/// Token base units bought with `lamports` at `tokens_per_sol`, `None` past `u64::MAX`
+pub fn presale_tokens(lamports: u64, tokens_per_sol: u64) -> Option<u64> {
+    u64::try_from(lamports as u128 * tokens_per_sol as u128 / LAMPORTS_PER_SOL as u128).ok()
+}
+
+/// Token base units a bonding curve price is quoted for
+pub const CURVE_PRICE_SCALE: u128 = 1_000_000_000;
+/// Most price steps a curve may span, bounding the compute used to price a trade
+pub const MAX_CURVE_STEPS: u64 = 100;
+
+/// Reserve backing `sold` tokens and the price of the next token, both scaled by
+/// `CURVE_PRICE_SCALE`; `None` past `MAX_CURVE_STEPS` or on overflow
+fn curve_walk(shape: &CurveShape, sold: u64) -> Option<(u128, u128)> {
+    let full_steps = sold / shape.step_size;
+    if full_steps > MAX_CURVE_STEPS {
+        return None;
+    }
+    let step_size = shape.step_size as u128;
+    let mut price = shape.start_price as u128;
+    let mut reserve: u128 = 0;
+    for _ in 0..full_steps {
+        reserve = reserve.checked_add(price.checked_mul(step_size)?)?;
+        price = match shape.kind {
+            CurveKind::Linear => price.checked_add(shape.growth as u128)?,
+            CurveKind::Exponential => price.checked_mul(10_000 + shape.growth as u128)? / 10_000,
+        };
+    }
+    let partial = (sold % shape.step_size) as u128;
+    Some((reserve.checked_add(price.checked_mul(partial)?)?, price))
+}
+
+/// Lamports backing `sold` tokens, scaled by `CURVE_PRICE_SCALE`
+pub fn curve_reserve(shape: &CurveShape, sold: u64) -> Option<u128> {
+    curve_walk(shape, sold).map(|(reserve, _)| reserve)
+}
+
+/// Lamports to buy `amount` tokens after `sold`, rounded up
+pub fn curve_buy_cost(shape: &CurveShape, sold: u64, amount: u64) -> Option<u64> {
+    let cost = curve_reserve(shape, sold.checked_add(amount)?)? - curve_reserve(shape, sold)?;
+    u64::try_from(cost.div_ceil(CURVE_PRICE_SCALE)).ok()
+}
+
+/// Lamports paid for selling `amount` tokens back from `sold`, rounded down
+pub fn curve_sell_proceeds(shape: &CurveShape, sold: u64, amount: u64) -> Option<u64> {
+    let proceeds = curve_reserve(shape, sold)? - curve_reserve(shape, sold.checked_sub(amount)?)?;
+    u64::try_from(proceeds / CURVE_PRICE_SCALE).ok()
+}
+
+/// Lamports `supply` tokens are worth at the curve price after `sold`
+pub fn curve_market_cap(shape: &CurveShape, sold: u64, supply: u64) -> Option<u64> {
+    let (_, price) = curve_walk(shape, sold)?;
+    u64::try_from(price.checked_mul(supply as u128)? / CURVE_PRICE_SCALE).ok()
+}
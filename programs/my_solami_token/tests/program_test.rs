@@ -0,0 +1,2798 @@
+//! In-process integration tests running the program under `solana-program-test`.
+
+use anchor_lang::prelude::AccountInfo;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    error::TokenError,
+    extension::{
+        confidential_transfer::{self, ConfidentialTransferMint},
+        transfer_fee, transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    proof::ProofLocation,
+    solana_zk_token_sdk::{
+        encryption::{auth_encryption::AeKey, elgamal::ElGamalKeypair},
+        instruction::PubkeyValidityData,
+        zk_token_elgamal::pod::ElGamalPubkey,
+    },
+};
+use my_solami_token::{
+    errors::ErrorCode,
+    state::{BatchTransferItem, TokenState, TransferFeeParams, MAX_BATCH_TRANSFERS},
+};
+use my_solami_token_client::{accounts, instructions, pda};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id, instruction::create_associated_token_account,
+};
+
+const TOTAL_SUPPLY: u64 = 1_000_000;
+const LAUNCH_GATE: i64 = 300;
+const REWARD_START_DELAY: i64 = 2520;
+
+/// Anchor's `entry` ties the account slice to the `AccountInfo` lifetime
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    my_solami_token::entry(program_id, accounts, data)
+}
+
+/// Mints the harness can run against
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MintKind {
+    Token,
+    /// Token-2022 mint whose transfer hook calls back into the program
+    TransferHook,
+    /// Token-2022 mint withholding a 10% transfer fee, as `initialize_token` sets it
+    TransferFee,
+    /// Token-2022 mint created by `initialize_confidential_mint`
+    Confidential,
+}
+
+impl MintKind {
+    fn token_program(self) -> Pubkey {
+        match self {
+            MintKind::Token => spl_token::id(),
+            MintKind::TransferHook | MintKind::TransferFee | MintKind::Confidential => {
+                spl_token_2022::id()
+            }
+        }
+    }
+}
+
+struct Harness {
+    ctx: ProgramTestContext,
+    admin: Keypair,
+    mint_kind: MintKind,
+    token_program: Pubkey,
+    mint: Pubkey,
+    admin_ata: Pubkey,
+    lp_pool: Pubkey,
+    rewards_pool: Pubkey,
+    lp_fund: Pubkey,
+    staking_pool: Pubkey,
+    swap_wallet: Pubkey,
+    whitelisted: Keypair,
+    whitelisted_ata: Pubkey,
+}
+
+impl Harness {
+    /// Create the mint and pools, then run `initialize_token`
+    async fn new() -> Self {
+        Self::with_mint(MintKind::Token).await
+    }
+
+    async fn with_mint(mint_kind: MintKind) -> Self {
+        let mut harness = Self::without_init_with_mint(mint_kind).await;
+        harness.initialize().await.unwrap();
+        harness
+    }
+
+    /// Mint owned by the `mint_authority` PDA and empty pools, nothing initialized
+    async fn without_init() -> Self {
+        Self::without_init_with_mint(MintKind::Token).await
+    }
+
+    async fn without_init_with_mint(mint_kind: MintKind) -> Self {
+        let program = ProgramTest::new(
+            "my_solami_token",
+            my_solami_token::ID,
+            processor!(process_instruction),
+        );
+        let mut ctx = program.start_with_context().await;
+
+        let admin = Keypair::new();
+        let fund =
+            system_instruction::transfer(&ctx.payer.pubkey(), &admin.pubkey(), 10_000_000_000);
+        send(&mut ctx, &[fund], &[]).await.unwrap();
+
+        let mint = create_mint_of(&mut ctx, mint_kind).await;
+
+        let admin_ata = create_ata(&mut ctx, &mint, &admin.pubkey()).await;
+        let lp_pool = create_token_account(&mut ctx, &mint, &admin.pubkey()).await;
+        let rewards_pool = create_token_account(&mut ctx, &mint, &admin.pubkey()).await;
+        let lp_fund = create_token_account(&mut ctx, &mint, &admin.pubkey()).await;
+        let staking_pool = create_token_account(&mut ctx, &mint, &admin.pubkey()).await;
+        let swap_wallet = create_token_account(&mut ctx, &mint, &admin.pubkey()).await;
+
+        let whitelisted = Keypair::new();
+        let whitelisted_ata = create_ata(&mut ctx, &mint, &whitelisted.pubkey()).await;
+
+        Self {
+            ctx,
+            admin,
+            mint_kind,
+            token_program: mint_kind.token_program(),
+            mint,
+            admin_ata,
+            lp_pool,
+            rewards_pool,
+            lp_fund,
+            staking_pool,
+            swap_wallet,
+            whitelisted,
+            whitelisted_ata,
+        }
+    }
+
+    async fn initialize(&mut self) -> Result<(), BanksClientError> {
+        self.initialize_with_presale(0, None).await
+    }
+
+    /// `initialize_token` escrowing `tranche` tokens in `presale_escrow`
+    async fn initialize_with_presale(
+        &mut self,
+        tranche: u64,
+        presale_escrow: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        self.initialize_with(tranche, presale_escrow, None).await
+    }
+
+    /// `initialize_token` seeding `curve_reserve` with the LP share
+    async fn initialize_with_curve(
+        &mut self,
+        curve_reserve: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        self.initialize_with(0, None, Some(curve_reserve)).await
+    }
+
+    async fn initialize_with(
+        &mut self,
+        tranche: u64,
+        presale_escrow: Option<Pubkey>,
+        curve_reserve: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let ix = instructions::initialize_token(
+            &instructions::InitializeTokenAccounts {
+                mint: self.mint,
+                admin: self.admin.pubkey(),
+                admin_token_account: self.admin_ata,
+                lp_pool: self.lp_pool,
+                rewards_pool: self.rewards_pool,
+                lp_fund: self.lp_fund,
+                staking_pool: self.staking_pool,
+                swap_wallet: self.swap_wallet,
+                token_account: self.admin_ata,
+                presale_escrow,
+                curve_reserve,
+                token_program: self.token_program,
+            },
+            TOTAL_SUPPLY,
+            vec![self.whitelisted.pubkey()],
+            (self.mint_kind == MintKind::TransferFee).then_some(TransferFeeParams {
+                basis_points: 1_000,
+                maximum_fee: u64::MAX,
+            }),
+            tranche,
+        );
+        let admin = self.admin.insecure_clone();
+        self.send(&[ix], &[&admin]).await
+    }
+
+    async fn send(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        send(&mut self.ctx, ixs, signers).await
+    }
+
+    /// `transfer_tokens` accounts without holder records
+    fn transfer_keys(
+        &self,
+        sender: Pubkey,
+        receiver: Pubkey,
+        authority: Pubkey,
+    ) -> instructions::TransferTokensAccounts {
+        instructions::TransferTokensAccounts {
+            mint: self.mint,
+            sender,
+            receiver,
+            authority,
+            rewards_pool: self.rewards_pool,
+            lp_fund: self.lp_fund,
+            lp_pool: self.lp_pool,
+            token_program: self.token_program,
+            receiver_record: None,
+            sender_record: None,
+            rent_recipient: None,
+            payer: None,
+            sender_referral: None,
+            referrer_stats: None,
+            referral_vault: None,
+            loyalty_tiers: None,
+            sender_checkpoints: None,
+            receiver_checkpoints: None,
+        }
+    }
+
+    /// Owner-signed `transfer_tokens` from the admin's token account
+    async fn transfer(&mut self, receiver: Pubkey, amount: u64) -> Result<(), BanksClientError> {
+        let admin = self.admin.insecure_clone();
+        let keys = self.transfer_keys(self.admin_ata, receiver, admin.pubkey());
+        let ix = instructions::transfer_tokens(&keys, amount);
+        self.send(&[ix], &[&admin]).await
+    }
+
+    /// Token-2022 `transfer_checked` straight from the admin, bypassing the program
+    async fn direct_transfer(
+        &mut self,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let admin = self.admin.insecure_clone();
+        self.direct_transfer_from(self.admin_ata, &admin, receiver, amount)
+            .await
+    }
+
+    /// Token-2022 `transfer_checked` of `owner`'s `source`, bypassing the program
+    async fn direct_transfer_from(
+        &mut self,
+        source: Pubkey,
+        owner: &Keypair,
+        receiver: Pubkey,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let mut ix = spl_token_2022::instruction::transfer_checked(
+            &self.token_program,
+            &source,
+            &self.mint,
+            &receiver,
+            &owner.pubkey(),
+            &[],
+            amount,
+            6,
+        )
+        .unwrap();
+        if self.mint_kind == MintKind::TransferHook {
+            ix.accounts.extend([
+                AccountMeta::new_readonly(pda::extra_account_metas(&self.mint).0, false),
+                AccountMeta::new_readonly(pda::token_state(&self.mint).0, false),
+                AccountMeta::new_readonly(pda::whitelist(&self.mint).0, false),
+                AccountMeta::new(pda::checkpoints(&source).0, false),
+                AccountMeta::new(pda::checkpoints(&receiver).0, false),
+                AccountMeta::new_readonly(my_solami_token::ID, false),
+            ]);
+        }
+        self.send(&[ix], &[owner]).await
+    }
+
+    /// Move the bank clock forward by `seconds`
+    async fn warp(&mut self, seconds: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    async fn account(&mut self, key: &Pubkey) -> Account {
+        self.ctx
+            .banks_client
+            .get_account(*key)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    async fn balance(&mut self, key: Pubkey) -> u64 {
+        let account = self.account(&key).await;
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    /// Simulate `balance_at` and decode its return data
+    async fn balance_at(&mut self, token_account: Pubkey, snapshot_id: u64) -> u64 {
+        let ix = instructions::balance_at(&self.mint, &token_account, snapshot_id);
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.ctx.payer.pubkey()),
+            &[&self.ctx.payer],
+            blockhash,
+        );
+        let simulation = self
+            .ctx
+            .banks_client
+            .simulate_transaction(tx)
+            .await
+            .unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        u64::from_le_bytes(return_data.data[..8].try_into().unwrap())
+    }
+
+    async fn supply(&mut self) -> u64 {
+        let mint = self.mint;
+        let account = self.account(&mint).await;
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data)
+            .unwrap()
+            .base
+            .supply
+    }
+
+    /// Initialize a second mint administered by `attacker`, returning the mint
+    async fn foreign_mint(&mut self, attacker: &Keypair) -> Pubkey {
+        let fund = system_instruction::transfer(
+            &self.ctx.payer.pubkey(),
+            &attacker.pubkey(),
+            10_000_000_000,
+        );
+        self.send(&[fund], &[]).await.unwrap();
+
+        let mint = create_mint(&mut self.ctx).await;
+        let owner = attacker.pubkey();
+        let attacker_ata = create_ata(&mut self.ctx, &mint, &owner).await;
+        let lp_pool = create_token_account(&mut self.ctx, &mint, &owner).await;
+        let rewards_pool = create_token_account(&mut self.ctx, &mint, &owner).await;
+        let lp_fund = create_token_account(&mut self.ctx, &mint, &owner).await;
+        let staking_pool = create_token_account(&mut self.ctx, &mint, &owner).await;
+        let wallet = Keypair::new().pubkey();
+        create_ata(&mut self.ctx, &mint, &wallet).await;
+
+        let ix = instructions::initialize_token(
+            &instructions::InitializeTokenAccounts {
+                mint,
+                admin: owner,
+                admin_token_account: attacker_ata,
+                lp_pool,
+                rewards_pool,
+                lp_fund,
+                staking_pool,
+                swap_wallet: staking_pool,
+                token_account: attacker_ata,
+                presale_escrow: None,
+                curve_reserve: None,
+                token_program: spl_token::id(),
+            },
+            TOTAL_SUPPLY,
+            vec![wallet],
+            None,
+            0,
+        );
+        self.send(&[ix], &[attacker]).await.unwrap();
+        mint
+    }
+
+    /// Fund a new wallet with `lamports`
+    async fn funded_wallet(&mut self, lamports: u64) -> Keypair {
+        let wallet = Keypair::new();
+        let ix = system_instruction::transfer(&self.ctx.payer.pubkey(), &wallet.pubkey(), lamports);
+        self.send(&[ix], &[]).await.unwrap();
+        wallet
+    }
+
+    async fn now(&mut self) -> i64 {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp
+    }
+
+    async fn token_state(&mut self) -> TokenState {
+        let key = pda::token_state(&self.mint).0;
+        let account = self.account(&key).await;
+        accounts::decode_token_state(&account.data).unwrap()
+    }
+}
+
+async fn send(
+    ctx: &mut ProgramTestContext,
+    ixs: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx =
+        Transaction::new_signed_with_payer(ixs, Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await
+}
+
+/// Mint whose authority is the `mint_authority` PDA, as `initialize_token` expects
+async fn create_mint(ctx: &mut ProgramTestContext) -> Pubkey {
+    create_mint_of(ctx, MintKind::Token).await
+}
+
+/// Mint of `kind` whose mint, hook and fee authorities are the `mint_authority` PDA
+async fn create_mint_of(ctx: &mut ProgramTestContext, kind: MintKind) -> Pubkey {
+    let mint = Keypair::new();
+    let authority = pda::mint_authority(&mint.pubkey()).0;
+    let token_program = kind.token_program();
+    if kind == MintKind::Confidential {
+        let ix = instructions::initialize_confidential_mint(
+            &ctx.payer.pubkey(),
+            &mint.pubkey(),
+            6,
+            Some([7; 32]),
+        );
+        send(ctx, &[ix], &[&mint]).await.unwrap();
+        return mint.pubkey();
+    }
+    let (extension, init_extension) = match kind {
+        MintKind::Token | MintKind::Confidential => (None, None),
+        MintKind::TransferHook => (
+            Some(ExtensionType::TransferHook),
+            Some(
+                transfer_hook::instruction::initialize(
+                    &token_program,
+                    &mint.pubkey(),
+                    Some(authority),
+                    Some(my_solami_token::ID),
+                )
+                .unwrap(),
+            ),
+        ),
+        // Start at the fee `initialize_token` sets, which only takes effect two epochs later
+        MintKind::TransferFee => (
+            Some(ExtensionType::TransferFeeConfig),
+            Some(
+                transfer_fee::instruction::initialize_transfer_fee_config(
+                    &token_program,
+                    &mint.pubkey(),
+                    Some(&authority),
+                    Some(&authority),
+                    1_000,
+                    u64::MAX,
+                )
+                .unwrap(),
+            ),
+        ),
+    };
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(
+        &extension.into_iter().collect::<Vec<_>>(),
+    )
+    .unwrap();
+
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let mut ixs = vec![system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &mint.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &token_program,
+    )];
+    ixs.extend(init_extension);
+    ixs.push(
+        spl_token_2022::instruction::initialize_mint2(
+            &token_program,
+            &mint.pubkey(),
+            &authority,
+            None,
+            6,
+        )
+        .unwrap(),
+    );
+    send(ctx, &ixs, &[&mint]).await.unwrap();
+    mint.pubkey()
+}
+
+/// Token program owning `mint`
+async fn token_program_of(ctx: &mut ProgramTestContext, mint: &Pubkey) -> Pubkey {
+    ctx.banks_client
+        .get_account(*mint)
+        .await
+        .unwrap()
+        .unwrap()
+        .owner
+}
+
+async fn create_ata(ctx: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let token_program = token_program_of(ctx, mint).await;
+    let ix = create_associated_token_account(&ctx.payer.pubkey(), owner, mint, &token_program);
+    send(ctx, &[ix], &[]).await.unwrap();
+    get_associated_token_address_with_program_id(owner, mint, &token_program)
+}
+
+async fn create_token_account(
+    ctx: &mut ProgramTestContext,
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Pubkey {
+    let token_program = token_program_of(ctx, mint).await;
+    let mint_data = ctx
+        .banks_client
+        .get_account(*mint)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    let mint_extensions = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)
+        .unwrap()
+        .get_extension_types()
+        .unwrap();
+    let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Account>(
+        &ExtensionType::get_required_init_account_extensions(&mint_extensions),
+    )
+    .unwrap();
+
+    let account = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let ixs = [
+        system_instruction::create_account(
+            &ctx.payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &token_program,
+        ),
+        spl_token_2022::instruction::initialize_account3(
+            &token_program,
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+    ];
+    send(ctx, &ixs, &[&account]).await.unwrap();
+    account.pubkey()
+}
+
+/// Add and configure the confidential transfer extension on `token_account`,
+/// leaving it unapproved
+async fn configure_confidential_account(
+    ctx: &mut ProgramTestContext,
+    mint: &Pubkey,
+    token_account: &Pubkey,
+    owner: &Keypair,
+) {
+    let elgamal = ElGamalKeypair::new_rand();
+    let proof = PubkeyValidityData::new(&elgamal).unwrap();
+    let mut ixs = vec![spl_token_2022::instruction::reallocate(
+        &spl_token_2022::id(),
+        token_account,
+        &ctx.payer.pubkey(),
+        &owner.pubkey(),
+        &[],
+        &[ExtensionType::ConfidentialTransferAccount],
+    )
+    .unwrap()];
+    ixs.extend(
+        confidential_transfer::instruction::configure_account(
+            &spl_token_2022::id(),
+            token_account,
+            mint,
+            AeKey::new_rand().encrypt(0),
+            65_536,
+            &owner.pubkey(),
+            &[],
+            ProofLocation::InstructionOffset(1.try_into().unwrap(), &proof),
+        )
+        .unwrap(),
+    );
+    send(ctx, &ixs, &[owner]).await.unwrap();
+}
+
+/// Point every account meta of `ix` at `from` to `to` instead
+fn spoof(mut ix: Instruction, from: &Pubkey, to: &Pubkey) -> Instruction {
+    for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == *from) {
+        meta.pubkey = *to;
+    }
+    ix
+}
+
+fn assert_error(result: Result<(), BanksClientError>, code: impl Into<u32>) {
+    match result.expect_err("transaction should fail").unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(actual)) => {
+            assert_eq!(actual, code.into())
+        }
+        err => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn initialize_distributes_supply_to_remaining_accounts() {
+    let mut h = Harness::new().await;
+
+    assert_eq!(h.supply().await, TOTAL_SUPPLY);
+    assert_eq!(h.balance(h.lp_pool).await, 400_000);
+    assert_eq!(h.balance(h.admin_ata).await, 450_000);
+    assert_eq!(h.balance(h.whitelisted_ata).await, 150_000);
+
+    let state = h.token_state().await;
+    assert_eq!(state.admin, h.admin.pubkey());
+    assert_eq!(state.total_supply, TOTAL_SUPPLY);
+    assert_eq!(
+        state.reward_distribution_start_time,
+        state.launch_time + REWARD_START_DELAY
+    );
+    assert!(!state.is_frozen);
+
+    let whitelist = h.account(&pda::whitelist(&h.mint).0).await;
+    let whitelist = accounts::decode_whitelist(&whitelist.data).unwrap();
+    assert_eq!(whitelist.wallets, vec![h.whitelisted.pubkey()]);
+}
+
+#[tokio::test]
+async fn initialize_rejects_missing_whitelist_accounts() {
+    let mut h = Harness::without_init().await;
+    let mut ix = instructions::initialize_token(
+        &instructions::InitializeTokenAccounts {
+            mint: h.mint,
+            admin: h.admin.pubkey(),
+            admin_token_account: h.admin_ata,
+            lp_pool: h.lp_pool,
+            rewards_pool: h.rewards_pool,
+            lp_fund: h.lp_fund,
+            staking_pool: h.staking_pool,
+            swap_wallet: h.swap_wallet,
+            token_account: h.admin_ata,
+            presale_escrow: None,
+            curve_reserve: None,
+            token_program: spl_token::id(),
+        },
+        TOTAL_SUPPLY,
+        vec![h.whitelisted.pubkey()],
+        None,
+        0,
+    );
+    ix.accounts.pop();
+
+    let admin = h.admin.insecure_clone();
+    let result = h.send(&[ix], &[&admin]).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn transfers_are_gated_until_launch_delay_passes() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+
+    assert_error(
+        h.transfer(receiver, 1_000).await,
+        ErrorCode::TradingNotEnabled,
+    );
+
+    h.warp(LAUNCH_GATE).await;
+    h.transfer(receiver, 1_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn taxed_transfer_splits_tax_between_pools_and_burn() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+    let admin_ata = h.admin_ata;
+    h.warp(LAUNCH_GATE).await;
+
+    h.transfer(receiver, 10_000).await.unwrap();
+
+    assert_eq!(h.balance(receiver).await, 9_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 700);
+    assert_eq!(h.balance(h.lp_fund).await, 200);
+    assert_eq!(h.balance(admin_ata).await, 440_000);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY - 100);
+
+    let state = h.token_state().await;
+    assert_eq!(state.total_transactions, 1);
+    assert_eq!(state.total_tax_collected, 1_000);
+    assert_eq!(state.total_burned, 100);
+    assert_eq!(state.total_supply, TOTAL_SUPPLY - 100);
+}
+
+#[tokio::test]
+async fn whitelisted_transfer_is_untaxed() {
+    let mut h = Harness::new().await;
+    h.warp(LAUNCH_GATE).await;
+
+    let whitelisted_ata = h.whitelisted_ata;
+    h.transfer(whitelisted_ata, 10_000).await.unwrap();
+
+    assert_eq!(h.balance(whitelisted_ata).await, 160_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 0);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY);
+    assert_eq!(h.token_state().await.total_tax_collected, 0);
+}
+
+/// Hook harness with `initialize_transfer_hook` done and trading open
+async fn hook_harness() -> Harness {
+    let mut h = Harness::with_mint(MintKind::TransferHook).await;
+    let admin = h.admin.insecure_clone();
+    let ix = instructions::initialize_transfer_hook(&h.mint, &admin.pubkey());
+    h.send(&[ix], &[&admin]).await.unwrap();
+    h.warp(LAUNCH_GATE).await;
+    h
+}
+
+#[tokio::test]
+async fn transfer_hook_rejects_direct_taxed_transfers() {
+    let mut h = hook_harness().await;
+    assert!(h.token_state().await.transfer_hook_enabled);
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+
+    let result = h.direct_transfer(receiver, 10_000).await;
+    assert_error(result, ErrorCode::TaxedTransferRequired);
+    assert_eq!(h.balance(receiver).await, 0);
+}
+
+#[tokio::test]
+async fn transfer_hook_matches_the_whitelist_by_owner() {
+    let mut h = hook_harness().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+
+    // Whitelisting a token account address does not exempt it
+    let admin = h.admin.insecure_clone();
+    let ix = instructions::add_to_whitelist(&h.mint, &admin.pubkey(), receiver);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let result = h.direct_transfer(receiver, 10_000).await;
+    assert_error(result, ErrorCode::TaxedTransferRequired);
+
+    let whitelisted_ata = h.whitelisted_ata;
+    h.direct_transfer(whitelisted_ata, 10_000).await.unwrap();
+    assert_eq!(h.balance(whitelisted_ata).await, 160_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 0);
+}
+
+#[tokio::test]
+async fn program_transfers_toggle_the_transfer_hook_off_and_back_on() {
+    let mut h = hook_harness().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+
+    h.transfer(receiver, 10_000).await.unwrap();
+    assert_eq!(h.balance(receiver).await, 9_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 700);
+
+    let mint = h.mint;
+    let account = h.account(&mint).await;
+    let mint_state =
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    assert_eq!(
+        transfer_hook::get_program_id(&mint_state),
+        Some(my_solami_token::ID)
+    );
+
+    // The hook is back in place, so direct taxed transfers stay blocked
+    let result = h.direct_transfer(receiver, 1_000).await;
+    assert_error(result, ErrorCode::TaxedTransferRequired);
+}
+
+/// Confidential harness with the transfer hook registered and trading open
+async fn confidential_harness() -> Harness {
+    let mut h = Harness::with_mint(MintKind::Confidential).await;
+    let admin = h.admin.insecure_clone();
+    let ix = instructions::initialize_transfer_hook(&h.mint, &admin.pubkey());
+    h.send(&[ix], &[&admin]).await.unwrap();
+    h.warp(LAUNCH_GATE).await;
+    h
+}
+
+/// Confidential transfer config stored on the mint: auto-approve flag and auditor
+async fn confidential_mint_config(h: &mut Harness) -> (bool, Option<ElGamalPubkey>) {
+    let mint = h.mint;
+    let account = h.account(&mint).await;
+    let mint_state =
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data).unwrap();
+    let extension = mint_state
+        .get_extension::<ConfidentialTransferMint>()
+        .unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(extension.authority),
+        Some(pda::mint_authority(&mint).0)
+    );
+    (
+        bool::from(extension.auto_approve_new_accounts),
+        Option::<ElGamalPubkey>::from(extension.auditor_elgamal_pubkey),
+    )
+}
+
+#[tokio::test]
+async fn confidential_mint_never_auto_approves_accounts() {
+    let mut h = confidential_harness().await;
+    let admin = h.admin.insecure_clone();
+
+    assert_eq!(
+        confidential_mint_config(&mut h).await,
+        (false, Some(ElGamalPubkey([7; 32])))
+    );
+
+    let ix = instructions::update_confidential_config(&h.mint, &admin.pubkey(), None);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(confidential_mint_config(&mut h).await, (false, None));
+}
+
+#[tokio::test]
+async fn confidential_balances_are_limited_to_whitelisted_owners() {
+    let mut h = confidential_harness().await;
+    let admin = h.admin.insecure_clone();
+    let mint = h.mint;
+
+    // A taxed holder cannot be approved, so a direct deposit stays blocked
+    let holder = h.funded_wallet(LAMPORTS_PER_SOL).await;
+    let holder_ata = create_ata(&mut h.ctx, &mint, &holder.pubkey()).await;
+    h.transfer(holder_ata, 10_000).await.unwrap();
+    configure_confidential_account(&mut h.ctx, &mint, &holder_ata, &holder).await;
+
+    let ix = instructions::approve_confidential_account(&mint, &admin.pubkey(), &holder_ata);
+    let result = h.send(&[ix], &[&admin]).await;
+    assert_error(result, ErrorCode::ConfidentialNotWhitelisted);
+
+    let deposit = confidential_transfer::instruction::deposit(
+        &spl_token_2022::id(),
+        &holder_ata,
+        &mint,
+        1_000,
+        6,
+        &holder.pubkey(),
+        &[],
+    )
+    .unwrap();
+    let result = h.send(&[deposit], &[&holder]).await;
+    assert_error(
+        result,
+        TokenError::ConfidentialTransferAccountNotApproved as u32,
+    );
+    assert_eq!(h.balance(holder_ata).await, 9_000);
+
+    // Whitelisted owners are untaxed anyway and can be approved
+    let whitelisted = h.whitelisted.insecure_clone();
+    let whitelisted_ata = h.whitelisted_ata;
+    configure_confidential_account(&mut h.ctx, &mint, &whitelisted_ata, &whitelisted).await;
+    let ix = instructions::approve_confidential_account(&mint, &admin.pubkey(), &whitelisted_ata);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let keys = instructions::ConfidentialBalanceAccounts {
+        mint,
+        owner: whitelisted.pubkey(),
+        token_account: whitelisted_ata,
+        rent_recipient: None,
+    };
+    let ix = instructions::confidential_deposit(&keys, 50_000);
+    h.send(&[ix], &[&whitelisted]).await.unwrap();
+    assert_eq!(h.balance(whitelisted_ata).await, 100_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 700);
+}
+
+#[tokio::test]
+async fn harvested_transfer_fees_are_split_between_pools_and_burn() {
+    let mut h = Harness::with_mint(MintKind::TransferFee).await;
+    let mint = h.mint;
+    let fee_vault = create_token_account(&mut h.ctx, &mint, &pda::mint_authority(&mint).0).await;
+    h.warp(LAUNCH_GATE).await;
+
+    // Tokens already in the vault are not part of this harvest
+    h.direct_transfer(fee_vault, 5_000).await.unwrap();
+    assert_eq!(h.balance(fee_vault).await, 4_500);
+
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    h.transfer(receiver, 10_000).await.unwrap();
+    assert_eq!(h.balance(receiver).await, 9_000);
+
+    let keys = instructions::HarvestFeesAccounts {
+        mint,
+        fee_vault,
+        rewards_pool: h.rewards_pool,
+        lp_fund: h.lp_fund,
+    };
+    let ix = instructions::harvest_and_split_fees(&keys, &[receiver]);
+    h.send(&[ix], &[]).await.unwrap();
+
+    // Pool deposits pay the transfer fee again, withheld for the next harvest
+    assert_eq!(h.balance(h.rewards_pool).await, 630);
+    assert_eq!(h.balance(h.lp_fund).await, 180);
+    assert_eq!(h.balance(fee_vault).await, 4_500);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY - 100);
+
+    let state = h.token_state().await;
+    assert_eq!(state.total_tax_collected, 1_000);
+    assert_eq!(state.total_burned, 100);
+
+    // Nothing withheld on the sources means nothing to split
+    let ix = instructions::harvest_and_split_fees(&keys, &[receiver]);
+    assert_error(h.send(&[ix], &[]).await, ErrorCode::InvalidAmount);
+}
+
+#[tokio::test]
+async fn whitelist_rejects_duplicates_and_unknown_wallets() {
+    let mut h = Harness::new().await;
+    let admin = h.admin.insecure_clone();
+    let mint = h.mint;
+    let wallet = h.whitelisted.pubkey();
+
+    let ix = instructions::add_to_whitelist(&mint, &admin.pubkey(), wallet);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::AlreadyWhitelisted,
+    );
+
+    // One removal is enough, so the wallet pays tax again
+    let ix = instructions::remove_from_whitelist(&mint, &admin.pubkey(), wallet);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let ix = instructions::remove_from_whitelist(&mint, &admin.pubkey(), wallet);
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::NotInWhitelist);
+
+    h.warp(LAUNCH_GATE).await;
+    let whitelisted_ata = h.whitelisted_ata;
+    h.transfer(whitelisted_ata, 10_000).await.unwrap();
+    assert_eq!(h.balance(whitelisted_ata).await, 159_000);
+}
+
+#[tokio::test]
+async fn transfer_rejects_zero_and_excessive_amounts() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+    h.warp(LAUNCH_GATE).await;
+
+    assert_error(h.transfer(receiver, 0).await, ErrorCode::InvalidAmount);
+    assert_error(
+        h.transfer(receiver, 450_001).await,
+        ErrorCode::InsufficientBalance,
+    );
+}
+
+#[tokio::test]
+async fn transfers_need_the_owner_or_an_approved_delegate() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+    let (admin_ata, admin) = (h.admin_ata, h.admin.insecure_clone());
+    let router = Keypair::new();
+    h.warp(LAUNCH_GATE).await;
+
+    let keys = h.transfer_keys(admin_ata, receiver, router.pubkey());
+    assert_error(
+        h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&router])
+            .await,
+        ErrorCode::Unauthorized,
+    );
+    assert_error(
+        h.send(
+            &[instructions::transfer_tokens_from(&keys, 10_000)],
+            &[&router],
+        )
+        .await,
+        ErrorCode::Unauthorized,
+    );
+
+    let approve = spl_token::instruction::approve(
+        &spl_token::id(),
+        &admin_ata,
+        &router.pubkey(),
+        &admin.pubkey(),
+        &[],
+        15_000,
+    )
+    .unwrap();
+    h.send(&[approve], &[&admin]).await.unwrap();
+
+    // An approved delegate still can't act as the owner
+    assert_error(
+        h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&router])
+            .await,
+        ErrorCode::Unauthorized,
+    );
+
+    h.send(
+        &[instructions::transfer_tokens_from(&keys, 10_000)],
+        &[&router],
+    )
+    .await
+    .unwrap();
+    assert_eq!(h.balance(receiver).await, 9_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 700);
+    assert_eq!(h.balance(h.lp_fund).await, 200);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY - 100);
+
+    // The tax was drawn from the allowance along with the net amount
+    assert_error(
+        h.send(
+            &[instructions::transfer_tokens_from(&keys, 5_001)],
+            &[&router],
+        )
+        .await,
+        ErrorCode::InsufficientAllowance,
+    );
+    h.send(
+        &[instructions::transfer_tokens_from(&keys, 5_000)],
+        &[&router],
+    )
+    .await
+    .unwrap();
+    assert_eq!(h.balance(admin_ata).await, 435_000);
+}
+
+#[tokio::test]
+async fn batch_transfer_taxes_each_recipient_and_allocates_once() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let whitelisted_ata = h.whitelisted_ata;
+    let first = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let second = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    h.warp(LAUNCH_GATE).await;
+
+    let state = h.token_state().await;
+    let transfers = vec![
+        BatchTransferItem {
+            recipient: first,
+            amount: 10_000,
+        },
+        BatchTransferItem {
+            recipient: second,
+            amount: 20_000,
+        },
+        BatchTransferItem {
+            recipient: whitelisted_ata,
+            amount: 5_000,
+        },
+    ];
+    let batch = |transfers| {
+        instructions::batch_transfer(
+            &mint,
+            &admin_ata,
+            &admin.pubkey(),
+            &spl_token::id(),
+            &state,
+            None,
+            None,
+            transfers,
+        )
+    };
+
+    // Every listed recipient must be passed as a remaining account
+    let mut ix = batch(transfers.clone());
+    ix.accounts.pop();
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::InvalidBatchTransfer,
+    );
+
+    h.send(&[batch(transfers)], &[&admin]).await.unwrap();
+    assert_eq!(h.balance(first).await, 9_000);
+    assert_eq!(h.balance(second).await, 18_000);
+    assert_eq!(h.balance(whitelisted_ata).await, 155_000);
+    assert_eq!(h.balance(admin_ata).await, 415_000);
+
+    // The 3_000 combined tax is split once
+    assert_eq!(h.balance(h.rewards_pool).await, 2_100);
+    assert_eq!(h.balance(h.lp_fund).await, 600);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY - 300);
+
+    let state = h.token_state().await;
+    assert_eq!(state.total_transactions, 3);
+    assert_eq!(state.total_tax_collected, 3_000);
+    assert_eq!(state.total_burned, 300);
+
+    // Every recipient is a new holder, paid for by the sender's owner
+    assert_eq!(state.holder_count, 3);
+    let record = pda::holder_record(&mint, &second).0;
+    let record = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(record.rent_payer, admin.pubkey());
+}
+
+#[tokio::test]
+async fn batch_transfer_fits_the_account_lock_limit_at_the_cap() {
+    let mut h = Harness::new().await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    let (sender, sender_ata) = (h.whitelisted.insecure_clone(), h.whitelisted_ata);
+    let (rent_payer, batch_payer, referrer) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let payer = h.ctx.payer.pubkey();
+    for wallet in [rent_payer.pubkey(), batch_payer.pubkey(), sender.pubkey()] {
+        let ix = system_instruction::transfer(&payer, &wallet, LAMPORTS_PER_SOL);
+        h.send(&[ix], &[]).await.unwrap();
+    }
+    let ix = instructions::register_holder(&mint, &rent_payer.pubkey(), &sender_ata);
+    h.send(&[ix], &[&rent_payer]).await.unwrap();
+
+    // Loyalty tiers, a referrer and separate payers lock every account a batch can take
+    let tier = accounts::LoyaltyTier {
+        min_hold: 1_000,
+        min_balance: 100_000,
+        discount_bps: 5_000,
+    };
+    let ix = instructions::configure_loyalty_tiers(&mint, &admin.pubkey(), vec![tier]);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    h.warp(LAUNCH_GATE).await;
+
+    // Leave the sender exactly enough for a full batch
+    let keys = instructions::TransferTokensAccounts {
+        sender_record: Some(pda::holder_record(&mint, &sender_ata).0),
+        loyalty_tiers: Some(pda::loyalty_tiers(&mint).0),
+        ..h.transfer_keys(sender_ata, h.admin_ata, sender.pubkey())
+    };
+    let spare = 150_000 - MAX_BATCH_TRANSFERS as u64 * 10_000;
+    let ix = instructions::transfer_tokens(&keys, spare);
+    h.send(&[ix], &[&sender]).await.unwrap();
+
+    let vault = create_token_account(&mut h.ctx, &mint, &pda::mint_authority(&mint).0).await;
+    let ix = instructions::configure_referrals(&mint, &admin.pubkey(), &vault, 2_000);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let ix = instructions::register_referrer(&mint, &sender.pubkey(), &referrer.pubkey());
+    h.send(&[ix], &[&sender]).await.unwrap();
+
+    let mut transfers = Vec::new();
+    for _ in 0..=MAX_BATCH_TRANSFERS {
+        let recipient = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+        transfers.push(BatchTransferItem {
+            recipient,
+            amount: 10_000,
+        });
+    }
+    let state = h.token_state().await;
+    let batch = |rent_recipient, transfers| {
+        let mut ix = instructions::batch_transfer(
+            &mint,
+            &sender_ata,
+            &sender.pubkey(),
+            &spl_token::id(),
+            &state,
+            rent_recipient,
+            Some(&referrer.pubkey()),
+            transfers,
+        );
+        ix.accounts[11] = AccountMeta::new(batch_payer.pubkey(), true);
+        [
+            ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+            ix,
+        ]
+    };
+
+    // One recipient past the cap no longer fits in 64 account locks
+    let over = batch(Some(rent_payer.pubkey()), transfers.clone());
+    let result = h.send(&over, &[&sender, &batch_payer]).await;
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::TooManyAccountLocks
+    );
+
+    // The full batch empties the sender, whose record needs its rent payer to close
+    transfers.pop();
+    assert_error(
+        h.send(&batch(None, transfers.clone()), &[&sender, &batch_payer])
+            .await,
+        ErrorCode::MissingHolderAccounts,
+    );
+    let full = batch(Some(rent_payer.pubkey()), transfers.clone());
+    h.send(&full, &[&sender, &batch_payer]).await.unwrap();
+    assert_eq!(h.balance(sender_ata).await, 0);
+    for transfer in &transfers {
+        assert_eq!(h.balance(transfer.recipient).await, 9_000);
+    }
+    assert!(h
+        .ctx
+        .banks_client
+        .get_account(pda::holder_record(&mint, &sender_ata).0)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        h.token_state().await.holder_count,
+        MAX_BATCH_TRANSFERS as u64
+    );
+
+    // The referrer is credited from the batch's combined tax
+    let stats_key = pda::referrer_stats(&mint, &referrer.pubkey()).0;
+    let stats = accounts::decode_referrer_stats(&h.account(&stats_key).await.data).unwrap();
+    assert_eq!(stats.referred_tax, MAX_BATCH_TRANSFERS as u64 * 1_000);
+    assert!(stats.claimable > 0);
+    assert_eq!(h.balance(vault).await, stats.claimable);
+}
+
+#[tokio::test]
+async fn batch_transfer_discounts_loyal_senders_and_checkpoints_balances() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let first = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let second = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let payer = h.ctx.payer.pubkey();
+
+    let tier = accounts::LoyaltyTier {
+        min_hold: 1_000,
+        min_balance: 100_000,
+        discount_bps: 5_000,
+    };
+    let ix = instructions::configure_loyalty_tiers(&mint, &admin.pubkey(), vec![tier]);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let ix = instructions::register_holder(&mint, &payer, &admin_ata);
+    h.send(&[ix], &[]).await.unwrap();
+    h.warp(LAUNCH_GATE + 1_000).await;
+
+    let ix = instructions::take_snapshot(&mint, &admin.pubkey(), 1);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let state = h.token_state().await;
+    let transfers = vec![
+        BatchTransferItem {
+            recipient: first,
+            amount: 10_000,
+        },
+        BatchTransferItem {
+            recipient: second,
+            amount: 20_000,
+        },
+    ];
+    let ix = instructions::batch_transfer(
+        &mint,
+        &admin_ata,
+        &admin.pubkey(),
+        &spl_token::id(),
+        &state,
+        None,
+        None,
+        transfers,
+    );
+
+    // The sender's checkpoints come right after its holder record
+    let mut skipped = ix.clone();
+    let sender_checkpoints = skipped.accounts.len() - 7;
+    skipped.accounts[sender_checkpoints] = AccountMeta::new_readonly(my_solami_token::ID, false);
+    assert_error(
+        h.send(&[skipped], &[&admin]).await,
+        ErrorCode::AccountMismatch,
+    );
+
+    // Half of the 10% tax is waived on every transfer after the full hold
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.token_state().await.holder_count, 3);
+    assert_eq!(h.balance(first).await, 9_500);
+    assert_eq!(h.balance(second).await, 19_000);
+    assert_eq!(h.balance(admin_ata).await, 420_000);
+
+    assert_eq!(h.balance_at(admin_ata, 1).await, 450_000);
+    assert_eq!(h.balance_at(first, 1).await, 0);
+    assert_eq!(h.balance_at(second, 1).await, 0);
+}
+
+#[tokio::test]
+async fn frozen_contract_rejects_transfers() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    h.warp(LAUNCH_GATE).await;
+
+    let freeze = instructions::freeze_contract(&mint, &admin.pubkey(), true);
+    h.send(&[freeze], &[&admin]).await.unwrap();
+    assert!(h.token_state().await.is_frozen);
+    assert_error(h.transfer(receiver, 1_000).await, ErrorCode::ContractFrozen);
+
+    let unfreeze = instructions::freeze_contract(&mint, &admin.pubkey(), false);
+    h.send(&[unfreeze], &[&admin]).await.unwrap();
+    h.transfer(receiver, 1_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn manual_and_user_burns_reduce_supply() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+
+    let burn = instructions::manual_burn(
+        &mint,
+        &admin.pubkey(),
+        &admin_ata,
+        &spl_token::id(),
+        None,
+        50_000,
+    );
+    h.send(&[burn], &[&admin]).await.unwrap();
+
+    let holder = h.whitelisted.insecure_clone();
+    let holder_ata = h.whitelisted_ata;
+    let burn = instructions::user_burn(
+        &mint,
+        &holder.pubkey(),
+        &holder_ata,
+        &spl_token::id(),
+        None,
+        10_000,
+    );
+    h.send(&[burn], &[&holder]).await.unwrap();
+
+    assert_eq!(h.balance(admin_ata).await, 400_000);
+    assert_eq!(h.balance(holder_ata).await, 140_000);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY - 60_000);
+
+    let state = h.token_state().await;
+    assert_eq!(state.total_supply, TOTAL_SUPPLY - 60_000);
+    assert_eq!(state.total_burned, 60_000);
+
+    let burn = instructions::user_burn(
+        &mint,
+        &holder.pubkey(),
+        &holder_ata,
+        &spl_token::id(),
+        None,
+        140_001,
+    );
+    assert_error(
+        h.send(&[burn], &[&holder]).await,
+        ErrorCode::InsufficientBalance,
+    );
+}
+
+#[tokio::test]
+async fn emission_only_mints_into_the_rewards_or_staking_pool() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let rewards_pool = h.rewards_pool;
+
+    let configure = |recipient: &Pubkey| {
+        instructions::configure_emission(
+            &mint,
+            &admin.pubkey(),
+            recipient,
+            TOTAL_SUPPLY + 1_500,
+            1_000,
+            60,
+        )
+    };
+    assert_error(
+        h.send(&[configure(&admin_ata)], &[&admin]).await,
+        ErrorCode::InvalidEmissionConfig,
+    );
+    h.send(&[configure(&rewards_pool)], &[&admin])
+        .await
+        .unwrap();
+
+    // The second epoch is capped by the max supply, the third has nothing left
+    for expected in [1_000, 1_500] {
+        h.warp(60).await;
+        let ix = instructions::emit_epoch(&mint, &rewards_pool, &spl_token::id());
+        h.send(&[ix], &[]).await.unwrap();
+        assert_eq!(h.balance(rewards_pool).await, expected);
+    }
+    h.warp(60).await;
+    let ix = instructions::emit_epoch(&mint, &rewards_pool, &spl_token::id());
+    assert_error(h.send(&[ix], &[]).await, ErrorCode::MaxSupplyReached);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY + 1_500);
+}
+
+#[tokio::test]
+async fn admin_instructions_reject_other_signers() {
+    let mut h = Harness::new().await;
+    let mint = h.mint;
+    let intruder = h.whitelisted.insecure_clone();
+    let intruder_ata = h.whitelisted_ata;
+
+    let ix = instructions::freeze_contract(&mint, &intruder.pubkey(), true);
+    assert_error(
+        h.send(&[ix], &[&intruder]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+
+    let ix = instructions::manual_burn(
+        &mint,
+        &intruder.pubkey(),
+        &intruder_ata,
+        &spl_token::id(),
+        None,
+        1,
+    );
+    assert_error(
+        h.send(&[ix], &[&intruder]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+
+    let ix = instructions::transfer_ownership(&mint, &intruder.pubkey(), intruder.pubkey());
+    assert_error(
+        h.send(&[ix], &[&intruder]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+
+    let ix = instructions::add_to_whitelist(&mint, &intruder.pubkey(), intruder.pubkey());
+    assert_error(
+        h.send(&[ix], &[&intruder]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+
+    assert_eq!(h.token_state().await.admin, h.admin.pubkey());
+}
+
+#[tokio::test]
+async fn rewards_swap_after_reward_start_time() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    h.warp(LAUNCH_GATE).await;
+    h.transfer(receiver, 10_000).await.unwrap();
+
+    h.warp(REWARD_START_DELAY).await;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    assert!(clock.unix_timestamp >= h.token_state().await.reward_distribution_start_time);
+
+    let (swap_wallet, rewards_pool) = (h.swap_wallet, h.rewards_pool);
+    let ix = instructions::prepare_rewards_swap(
+        &mint,
+        &admin.pubkey(),
+        &rewards_pool,
+        &swap_wallet,
+        &spl_token::id(),
+        700,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    assert_eq!(h.balance(rewards_pool).await, 0);
+    assert_eq!(h.balance(swap_wallet).await, 700);
+}
+
+#[tokio::test]
+async fn reconcile_supply_only_lets_admin_correct_pools() {
+    let mut h = Harness::new().await;
+    let (mint, lp_pool, rewards_pool) = (h.mint, h.lp_pool, h.rewards_pool);
+    let staking_pool = h.staking_pool;
+    let caller = h.whitelisted.insecure_clone();
+    let admin = h.admin.insecure_clone();
+
+    // The LP pool was seeded at initialization but its balance was never recorded
+    assert_eq!(h.token_state().await.liquidity_pool_balance, 0);
+
+    let ix = instructions::reconcile_supply(
+        &mint,
+        &caller.pubkey(),
+        &lp_pool,
+        &rewards_pool,
+        &staking_pool,
+        true,
+    );
+    h.send(&[ix], &[&caller]).await.unwrap();
+    let state = h.token_state().await;
+    assert_eq!(state.total_supply, h.supply().await);
+    assert_eq!(state.liquidity_pool_balance, 0);
+
+    let ix = instructions::reconcile_supply(
+        &mint,
+        &admin.pubkey(),
+        &lp_pool,
+        &rewards_pool,
+        &staking_pool,
+        true,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.token_state().await.liquidity_pool_balance, 400_000);
+}
+
+#[tokio::test]
+async fn transfer_rejects_unbound_tax_pools() {
+    let mut h = Harness::new().await;
+    let receiver = create_ata(&mut h.ctx, &h.mint, &Keypair::new().pubkey()).await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    h.warp(LAUNCH_GATE).await;
+
+    // Token accounts of the right mint that the attacker controls
+    let attacker = Keypair::new().pubkey();
+    let fake_pool = create_token_account(&mut h.ctx, &mint, &attacker).await;
+
+    for pool in [h.rewards_pool, h.lp_fund, h.lp_pool] {
+        let ix = instructions::transfer_tokens(
+            &h.transfer_keys(admin_ata, receiver, admin.pubkey()),
+            10_000,
+        );
+        assert_error(
+            h.send(&[spoof(ix, &pool, &fake_pool)], &[&admin]).await,
+            anchor_lang::error::ErrorCode::ConstraintHasOne,
+        );
+    }
+
+    assert_eq!(h.balance(fake_pool).await, 0);
+    assert_eq!(h.token_state().await.total_transactions, 0);
+}
+
+#[tokio::test]
+async fn instructions_reject_accounts_of_another_mint() {
+    let mut h = Harness::new().await;
+    let attacker = Keypair::new();
+    let foreign = h.foreign_mint(&attacker).await;
+    let mint = h.mint;
+
+    // The attacker administers the foreign mint's whitelist, not this one
+    let ix = instructions::add_to_whitelist(&mint, &attacker.pubkey(), attacker.pubkey());
+    let ix = spoof(
+        ix,
+        &pda::token_state(&mint).0,
+        &pda::token_state(&foreign).0,
+    );
+    let ix = spoof(ix, &pda::whitelist(&mint).0, &pda::whitelist(&foreign).0);
+    assert_error(
+        h.send(&[ix], &[&attacker]).await,
+        anchor_lang::error::ErrorCode::ConstraintSeeds,
+    );
+
+    let ix = instructions::freeze_contract(&mint, &attacker.pubkey(), true);
+    let ix = spoof(
+        ix,
+        &pda::token_state(&mint).0,
+        &pda::token_state(&foreign).0,
+    );
+    assert_error(
+        h.send(&[ix], &[&attacker]).await,
+        anchor_lang::error::ErrorCode::ConstraintSeeds,
+    );
+
+    h.warp(LAUNCH_GATE).await;
+    let receiver = create_ata(&mut h.ctx, &foreign, &attacker.pubkey()).await;
+    assert_error(
+        h.transfer(receiver, 10_000).await,
+        anchor_lang::error::ErrorCode::ConstraintTokenMint,
+    );
+
+    let state = h.token_state().await;
+    assert!(!state.is_frozen);
+    assert_eq!(state.total_transactions, 0);
+}
+
+#[tokio::test]
+async fn user_burn_rejects_accounts_the_signer_does_not_own() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata) = (h.mint, h.admin_ata);
+    let holder = h.whitelisted.insecure_clone();
+
+    let burn = instructions::user_burn(
+        &mint,
+        &holder.pubkey(),
+        &admin_ata,
+        &spl_token::id(),
+        None,
+        1,
+    );
+    assert_error(
+        h.send(&[burn], &[&holder]).await,
+        anchor_lang::error::ErrorCode::ConstraintTokenOwner,
+    );
+    assert_eq!(h.supply().await, TOTAL_SUPPLY);
+}
+
+#[tokio::test]
+async fn initialize_stores_pools_and_bumps() {
+    let mut h = Harness::new().await;
+    let mint = h.mint;
+    let state = h.token_state().await;
+
+    assert_eq!(state.lp_pool, h.lp_pool);
+    assert_eq!(state.rewards_pool, h.rewards_pool);
+    assert_eq!(state.lp_fund, h.lp_fund);
+    assert_eq!(state.staking_pool, h.staking_pool);
+    assert_eq!(state.swap_wallet, h.swap_wallet);
+    assert_eq!(state.bump, pda::token_state(&mint).1);
+    assert_eq!(state.whitelist_bump, pda::whitelist(&mint).1);
+    assert_eq!(state.mint_authority_bump, pda::mint_authority(&mint).1);
+}
+
+#[tokio::test]
+async fn set_pool_address_is_admin_only_and_rebinds_the_pool() {
+    let mut h = Harness::new().await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    let intruder = h.whitelisted.insecure_clone();
+    let new_wallet = create_token_account(&mut h.ctx, &mint, &admin.pubkey()).await;
+
+    let ix = instructions::set_pool_address(
+        &mint,
+        &intruder.pubkey(),
+        accounts::PoolKind::SwapWallet,
+        &new_wallet,
+    );
+    assert_error(
+        h.send(&[ix], &[&intruder]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+
+    // Only token accounts of this mint can be bound
+    let attacker = Keypair::new();
+    let foreign = h.foreign_mint(&attacker).await;
+    let foreign_account = create_token_account(&mut h.ctx, &foreign, &admin.pubkey()).await;
+    let ix = instructions::set_pool_address(
+        &mint,
+        &admin.pubkey(),
+        accounts::PoolKind::SwapWallet,
+        &foreign_account,
+    );
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        anchor_lang::error::ErrorCode::ConstraintTokenMint,
+    );
+
+    let ix = instructions::set_pool_address(
+        &mint,
+        &admin.pubkey(),
+        accounts::PoolKind::SwapWallet,
+        &new_wallet,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.token_state().await.swap_wallet, new_wallet);
+
+    // The old swap wallet is no longer accepted by prepare_rewards_swap
+    let (old_wallet, rewards_pool) = (h.swap_wallet, h.rewards_pool);
+    let ix = instructions::prepare_rewards_swap(
+        &mint,
+        &admin.pubkey(),
+        &rewards_pool,
+        &old_wallet,
+        &spl_token::id(),
+        0,
+    );
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+}
+
+#[tokio::test]
+async fn update_tvl_data_reads_only_the_stored_pools() {
+    let mut h = Harness::new().await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    let (lp_pool, rewards_pool, staking_pool) = (h.lp_pool, h.rewards_pool, h.staking_pool);
+
+    // A funded account of the same mint cannot stand in for the staking pool
+    let admin_ata = h.admin_ata;
+    let ix =
+        instructions::update_tvl_data(&mint, &admin.pubkey(), &lp_pool, &rewards_pool, &admin_ata);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+
+    let ix = instructions::update_tvl_data(
+        &mint,
+        &admin.pubkey(),
+        &lp_pool,
+        &rewards_pool,
+        &staking_pool,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let state = h.token_state().await;
+    assert_eq!(state.liquidity_pool_balance, 400_000);
+    assert_eq!(state.staking_pool_balance, 0);
+}
+
+#[tokio::test]
+async fn transfers_open_and_close_holder_records() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let holder = Keypair::new();
+    let holder_ata = create_ata(&mut h.ctx, &mint, &holder.pubkey()).await;
+    let record = pda::holder_record(&mint, &holder_ata).0;
+    let payer = h.ctx.payer.pubkey();
+    h.warp(LAUNCH_GATE).await;
+
+    let mut keys = h.transfer_keys(admin_ata, holder_ata, admin.pubkey());
+    keys.receiver_record = Some(record);
+    assert_error(
+        h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+            .await,
+        ErrorCode::MissingHolderAccounts,
+    );
+
+    keys.payer = Some(payer);
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+        .await
+        .unwrap();
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let opened = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(opened.token_account, holder_ata);
+    assert_eq!(opened.owner, holder.pubkey());
+    assert_eq!(opened.first_seen, clock.unix_timestamp);
+    assert_eq!(opened.rent_payer, payer);
+    assert_eq!(h.token_state().await.holder_count, 1);
+
+    // A second receipt keeps the original record
+    h.warp(60).await;
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+        .await
+        .unwrap();
+    let record_after = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(record_after.first_seen, opened.first_seen);
+    assert_eq!(h.token_state().await.holder_count, 1);
+
+    // Emptying the holder closes the record and refunds the payer
+    let balance = h.balance(holder_ata).await;
+    let mut keys = h.transfer_keys(holder_ata, admin_ata, holder.pubkey());
+    keys.sender_record = Some(record);
+    keys.rent_recipient = Some(payer);
+    h.send(&[instructions::transfer_tokens(&keys, balance)], &[&holder])
+        .await
+        .unwrap();
+    assert!(h
+        .ctx
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(h.token_state().await.holder_count, 0);
+}
+
+#[tokio::test]
+async fn emptying_one_token_account_keeps_the_owners_other_records() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let holder = Keypair::new();
+    let first = create_ata(&mut h.ctx, &mint, &holder.pubkey()).await;
+    let second = create_token_account(&mut h.ctx, &mint, &holder.pubkey()).await;
+    let payer = h.ctx.payer.pubkey();
+    h.warp(LAUNCH_GATE).await;
+
+    for account in [first, second] {
+        let mut keys = h.transfer_keys(admin_ata, account, admin.pubkey());
+        keys.receiver_record = Some(pda::holder_record(&mint, &account).0);
+        keys.payer = Some(payer);
+        h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+            .await
+            .unwrap();
+    }
+    assert_eq!(h.token_state().await.holder_count, 2);
+
+    let mut keys = h.transfer_keys(first, admin_ata, holder.pubkey());
+    keys.sender_record = Some(pda::holder_record(&mint, &first).0);
+    keys.rent_recipient = Some(payer);
+    h.send(&[instructions::transfer_tokens(&keys, 9_000)], &[&holder])
+        .await
+        .unwrap();
+
+    assert_eq!(h.token_state().await.holder_count, 1);
+    let record = pda::holder_record(&mint, &second).0;
+    let record = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(record.token_account, second);
+    assert_eq!(record.owner, holder.pubkey());
+    assert_eq!(h.balance(second).await, 9_000);
+}
+
+#[tokio::test]
+async fn register_holder_counts_existing_holders_once() {
+    let mut h = Harness::new().await;
+    let mint = h.mint;
+    let payer = h.ctx.payer.pubkey();
+    let (holder, holder_ata) = (h.whitelisted.pubkey(), h.whitelisted_ata);
+
+    let empty_owner = Keypair::new().pubkey();
+    let empty_ata = create_ata(&mut h.ctx, &mint, &empty_owner).await;
+    let ix = instructions::register_holder(&mint, &payer, &empty_ata);
+    assert_error(h.send(&[ix], &[]).await, ErrorCode::InsufficientBalance);
+
+    let ix = instructions::register_holder(&mint, &payer, &holder_ata);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_eq!(h.token_state().await.holder_count, 1);
+
+    let record = pda::holder_record(&mint, &holder_ata).0;
+    let record = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(record.mint, mint);
+    assert_eq!(record.token_account, holder_ata);
+    assert_eq!(record.owner, holder);
+
+    let ix = instructions::register_holder(&mint, &payer, &holder_ata);
+    assert!(h.send(&[ix], &[]).await.is_err());
+    assert_eq!(h.token_state().await.holder_count, 1);
+}
+
+#[tokio::test]
+async fn burning_a_holder_to_zero_closes_its_record() {
+    let mut h = Harness::new().await;
+    let mint = h.mint;
+    let payer = h.ctx.payer.pubkey();
+    let (holder, holder_ata) = (h.whitelisted.insecure_clone(), h.whitelisted_ata);
+    let record = pda::holder_record(&mint, &holder_ata).0;
+    let ix = instructions::register_holder(&mint, &payer, &holder_ata);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_eq!(h.token_state().await.holder_count, 1);
+
+    let burn = |rent_recipient| {
+        instructions::user_burn(
+            &mint,
+            &holder.pubkey(),
+            &holder_ata,
+            &spl_token::id(),
+            rent_recipient,
+            150_000,
+        )
+    };
+    let mut skipped = burn(Some(payer));
+    skipped.accounts[8] = AccountMeta::new_readonly(my_solami_token::ID, false);
+    assert_error(
+        h.send(&[skipped], &[&holder]).await,
+        ErrorCode::MissingHolderAccounts,
+    );
+    assert_error(
+        h.send(&[burn(None)], &[&holder]).await,
+        ErrorCode::MissingHolderAccounts,
+    );
+    assert_error(
+        h.send(&[burn(Some(holder.pubkey()))], &[&holder]).await,
+        ErrorCode::AccountMismatch,
+    );
+
+    h.send(&[burn(Some(payer))], &[&holder]).await.unwrap();
+    assert_eq!(h.balance(holder_ata).await, 0);
+    assert!(h
+        .ctx
+        .banks_client
+        .get_account(record)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(h.token_state().await.holder_count, 0);
+}
+
+#[tokio::test]
+async fn launch_phases_gate_transfers_in_order() {
+    let mut h = Harness::new().await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    let whitelisted_ata = h.whitelisted_ata;
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let launch = h.token_state().await.launch_time;
+
+    let ix = instructions::configure_launch_phases(
+        &mint,
+        &admin.pubkey(),
+        launch + 100,
+        launch + 50,
+        launch + 400,
+        5_000,
+    );
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::InvalidLaunchSchedule,
+    );
+    let intruder = h.whitelisted.insecure_clone();
+    let ix = instructions::configure_launch_phases(
+        &mint,
+        &intruder.pubkey(),
+        launch + 100,
+        launch + 200,
+        launch + 400,
+        5_000,
+    );
+    assert_error(
+        h.send(&[ix], &[&intruder]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+
+    let ix = instructions::configure_launch_phases(
+        &mint,
+        &admin.pubkey(),
+        launch + 100,
+        launch + 200,
+        launch + 400,
+        5_000,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_error(
+        h.transfer(receiver, 1_000).await,
+        ErrorCode::TradingNotEnabled,
+    );
+
+    h.warp(100).await;
+    assert_error(
+        h.transfer(receiver, 1_000).await,
+        ErrorCode::WhitelistOnlyPhase,
+    );
+    h.transfer(whitelisted_ata, 1_000).await.unwrap();
+    assert_eq!(
+        h.token_state().await.launch_phase,
+        accounts::LaunchPhase::WhitelistOnly
+    );
+
+    h.warp(100).await;
+    assert_error(
+        h.transfer(receiver, 5_001).await,
+        ErrorCode::TransferLimitExceeded,
+    );
+    h.transfer(receiver, 5_000).await.unwrap();
+    assert_eq!(
+        h.token_state().await.launch_phase,
+        accounts::LaunchPhase::Limited
+    );
+
+    h.warp(200).await;
+    h.transfer(receiver, 10_000).await.unwrap();
+    assert_eq!(
+        h.token_state().await.launch_phase,
+        accounts::LaunchPhase::Public
+    );
+
+    let ix = instructions::configure_launch_phases(
+        &mint,
+        &admin.pubkey(),
+        launch + 1_000,
+        launch + 2_000,
+        launch + 3_000,
+        5_000,
+    );
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::LaunchComplete);
+}
+
+#[tokio::test]
+async fn launch_tax_decays_to_the_base_rate() {
+    let mut h = Harness::new().await;
+    let (mint, admin) = (h.mint, h.admin.insecure_clone());
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let launch = h.token_state().await.launch_time;
+
+    let ix = instructions::configure_launch_tax(&mint, &admin.pubkey(), 999, 1_200, 4);
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::InvalidLaunchTax);
+    let ix = instructions::configure_launch_tax(&mint, &admin.pubkey(), 10_001, 1_200, 4);
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::InvalidLaunchTax);
+
+    // 50% decaying in four steps of 300s, counted from trading opening well after launch
+    let open = launch + 2_000;
+    let ix = instructions::configure_launch_phases(&mint, &admin.pubkey(), open, open, open, 1);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let ix = instructions::configure_launch_tax(&mint, &admin.pubkey(), 5_000, 1_200, 4);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    h.warp(2_000).await;
+    h.transfer(receiver, 10_000).await.unwrap();
+    assert_eq!(h.balance(receiver).await, 5_000);
+    assert_eq!(h.token_state().await.total_tax_collected, 5_000);
+
+    h.warp(900).await;
+    h.transfer(receiver, 10_000).await.unwrap();
+    assert_eq!(h.balance(receiver).await, 13_000);
+    assert_eq!(h.token_state().await.total_tax_collected, 7_000);
+
+    h.warp(300).await;
+    h.transfer(receiver, 10_000).await.unwrap();
+    assert_eq!(h.balance(receiver).await, 22_000);
+    assert_eq!(h.token_state().await.total_tax_collected, 8_000);
+
+    let ix = instructions::configure_launch_tax(&mint, &admin.pubkey(), 5_000, 1_200, 4);
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::LaunchTaxLocked);
+}
+
+const PRESALE_TRANCHE: u64 = 100_000;
+
+/// Initialize with a presale tranche and configure a sale open for 100 seconds
+/// at 50_000 tokens per SOL, with a 1 SOL soft cap and 2 SOL hard cap
+async fn presale_harness() -> (Harness, Pubkey) {
+    let mut h = Harness::without_init().await;
+    let mint = h.mint;
+    let presale = pda::presale(&mint).0;
+
+    let stray = create_token_account(&mut h.ctx, &mint, &h.admin.pubkey()).await;
+    assert_error(
+        h.initialize_with_presale(PRESALE_TRANCHE, Some(stray))
+            .await,
+        ErrorCode::InvalidPresale,
+    );
+    assert_error(
+        h.initialize_with_presale(PRESALE_TRANCHE, None).await,
+        ErrorCode::InvalidPresale,
+    );
+    let escrow = create_token_account(&mut h.ctx, &mint, &presale).await;
+    h.initialize_with_presale(PRESALE_TRANCHE, Some(escrow))
+        .await
+        .unwrap();
+
+    let admin = h.admin.insecure_clone();
+    let now = h.now().await;
+    // 60_000 tokens per SOL would sell 120_000 at the hard cap
+    let ix = instructions::configure_presale(
+        &mint,
+        &admin.pubkey(),
+        60_000,
+        LAMPORTS_PER_SOL,
+        2 * LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL * 3 / 2,
+        now,
+        now + 100,
+    );
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::InvalidPresale);
+    let ix = instructions::configure_presale(
+        &mint,
+        &admin.pubkey(),
+        50_000,
+        LAMPORTS_PER_SOL,
+        2 * LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL * 3 / 2,
+        now,
+        now + 100,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    (h, escrow)
+}
+
+#[tokio::test]
+async fn initialize_escrows_presale_tranche_from_marketing() {
+    let (mut h, escrow) = presale_harness().await;
+    assert_eq!(h.balance(escrow).await, PRESALE_TRANCHE);
+    // Burn allocation plus marketing, less the tranche
+    assert_eq!(h.balance(h.admin_ata).await, 450_000 - PRESALE_TRANCHE);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY);
+
+    let state = h.token_state().await;
+    assert_eq!(state.presale_escrow, escrow);
+    assert_eq!(state.presale_tranche, PRESALE_TRANCHE);
+}
+
+#[tokio::test]
+async fn presale_sells_tranche_and_pays_admin() {
+    let (mut h, escrow) = presale_harness().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let presale = pda::presale(&mint).0;
+    let buyer = h.funded_wallet(5 * LAMPORTS_PER_SOL).await;
+    let other = h.funded_wallet(5 * LAMPORTS_PER_SOL).await;
+
+    let ix = instructions::contribute_presale(&mint, &buyer.pubkey(), LAMPORTS_PER_SOL * 8 / 5);
+    assert_error(
+        h.send(&[ix], &[&buyer]).await,
+        ErrorCode::PresaleCapExceeded,
+    );
+    let ix = instructions::contribute_presale(&mint, &buyer.pubkey(), LAMPORTS_PER_SOL);
+    h.send(&[ix], &[&buyer]).await.unwrap();
+    let ix = instructions::contribute_presale(&mint, &buyer.pubkey(), LAMPORTS_PER_SOL / 2);
+    h.send(&[ix], &[&buyer]).await.unwrap();
+    let ix = instructions::contribute_presale(&mint, &other.pubkey(), LAMPORTS_PER_SOL * 6 / 10);
+    assert_error(
+        h.send(&[ix], &[&other]).await,
+        ErrorCode::PresaleCapExceeded,
+    );
+    let ix = instructions::contribute_presale(&mint, &other.pubkey(), LAMPORTS_PER_SOL * 4 / 10);
+    h.send(&[ix], &[&other]).await.unwrap();
+
+    let state = accounts::decode_presale(&h.account(&presale).await.data).unwrap();
+    assert_eq!(state.total_raised, LAMPORTS_PER_SOL * 19 / 10);
+    assert_eq!(state.contributors, 2);
+
+    let buyer_ata = create_ata(&mut h.ctx, &mint, &buyer.pubkey()).await;
+    let claim = instructions::claim_presale(
+        &mint,
+        &buyer.pubkey(),
+        &buyer_ata,
+        &escrow,
+        &spl_token::id(),
+    );
+    assert_error(
+        h.send(&[claim.clone()], &[&buyer]).await,
+        ErrorCode::PresaleNotEnded,
+    );
+
+    h.warp(100).await;
+    let ix = instructions::contribute_presale(&mint, &other.pubkey(), 1);
+    assert_error(h.send(&[ix], &[&other]).await, ErrorCode::PresaleNotActive);
+    let ix = instructions::refund_presale(&mint, &buyer.pubkey());
+    assert_error(h.send(&[ix], &[&buyer]).await, ErrorCode::PresaleSoftCapMet);
+
+    h.send(&[claim], &[&buyer]).await.unwrap();
+    assert_eq!(h.balance(buyer_ata).await, 75_000);
+    let receipt = pda::presale_receipt(&mint, &buyer.pubkey()).0;
+    assert!(h
+        .ctx
+        .banks_client
+        .get_account(receipt)
+        .await
+        .unwrap()
+        .is_none());
+
+    let admin_lamports = h.account(&admin.pubkey()).await.lamports;
+    let finalize = instructions::finalize_presale(
+        &mint,
+        &admin.pubkey(),
+        &admin_ata,
+        &escrow,
+        &spl_token::id(),
+    );
+    h.send(&[finalize.clone()], &[&admin]).await.unwrap();
+    assert_eq!(
+        h.account(&admin.pubkey()).await.lamports,
+        admin_lamports + LAMPORTS_PER_SOL * 19 / 10
+    );
+    // 95_000 tokens were sold, so 5_000 go back to the admin
+    assert_eq!(
+        h.balance(admin_ata).await,
+        450_000 - PRESALE_TRANCHE + 5_000
+    );
+    assert_eq!(h.balance(escrow).await, 20_000);
+    assert_error(
+        h.send(&[finalize], &[&admin]).await,
+        ErrorCode::PresaleFinalized,
+    );
+
+    let other_ata = create_ata(&mut h.ctx, &mint, &other.pubkey()).await;
+    let ix = instructions::claim_presale(
+        &mint,
+        &other.pubkey(),
+        &other_ata,
+        &escrow,
+        &spl_token::id(),
+    );
+    h.send(&[ix], &[&other]).await.unwrap();
+    assert_eq!(h.balance(other_ata).await, 20_000);
+    assert_eq!(h.balance(escrow).await, 0);
+}
+
+#[tokio::test]
+async fn presale_refunds_when_soft_cap_is_missed() {
+    let (mut h, escrow) = presale_harness().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let buyer = h.funded_wallet(5 * LAMPORTS_PER_SOL).await;
+
+    let ix = instructions::contribute_presale(&mint, &buyer.pubkey(), LAMPORTS_PER_SOL / 2);
+    h.send(&[ix], &[&buyer]).await.unwrap();
+    let contributed_balance = h.account(&buyer.pubkey()).await.lamports;
+    let receipt = pda::presale_receipt(&mint, &buyer.pubkey()).0;
+    let receipt_rent = h.account(&receipt).await.lamports;
+
+    h.warp(100).await;
+    let buyer_ata = create_ata(&mut h.ctx, &mint, &buyer.pubkey()).await;
+    let ix = instructions::claim_presale(
+        &mint,
+        &buyer.pubkey(),
+        &buyer_ata,
+        &escrow,
+        &spl_token::id(),
+    );
+    assert_error(
+        h.send(&[ix], &[&buyer]).await,
+        ErrorCode::PresaleSoftCapNotMet,
+    );
+
+    let ix = instructions::refund_presale(&mint, &buyer.pubkey());
+    h.send(&[ix], &[&buyer]).await.unwrap();
+    assert_eq!(
+        h.account(&buyer.pubkey()).await.lamports,
+        contributed_balance + LAMPORTS_PER_SOL / 2 + receipt_rent
+    );
+
+    let ix = instructions::finalize_presale(
+        &mint,
+        &admin.pubkey(),
+        &admin_ata,
+        &escrow,
+        &spl_token::id(),
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.balance(admin_ata).await, 450_000);
+    assert_eq!(h.balance(escrow).await, 0);
+}
+
+/// Linear curve from 1_000 lamports per token, rising 100 every 10_000 sold,
+/// migrating once the price reaches 1_450 (a 1.45 SOL market cap)
+fn test_curve() -> accounts::CurveShape {
+    accounts::CurveShape {
+        kind: accounts::CurveKind::Linear,
+        start_price: 1_000_000_000_000,
+        growth: 100_000_000_000,
+        step_size: 10_000,
+    }
+}
+
+#[tokio::test]
+async fn bonding_curve_trades_and_migrates_to_lp() {
+    let mut h = Harness::without_init().await;
+    let (mint, lp_pool, admin) = (h.mint, h.lp_pool, h.admin.insecure_clone());
+    let bonding_curve = pda::bonding_curve(&mint).0;
+
+    let stray = create_token_account(&mut h.ctx, &mint, &admin.pubkey()).await;
+    assert_error(
+        h.initialize_with_curve(stray).await,
+        ErrorCode::InvalidBondingCurve,
+    );
+    let reserve = create_token_account(&mut h.ctx, &mint, &bonding_curve).await;
+    h.initialize_with_curve(reserve).await.unwrap();
+    assert_eq!(h.balance(reserve).await, 400_000);
+    assert_eq!(h.balance(lp_pool).await, 0);
+
+    // 400 steps of 1_000 tokens is past the step bound
+    let ix = instructions::configure_bonding_curve(
+        &mint,
+        &admin.pubkey(),
+        &reserve,
+        accounts::CurveShape {
+            step_size: 1_000,
+            ..test_curve()
+        },
+        1_450_000_000,
+    );
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::InvalidBondingCurve,
+    );
+    let ix = instructions::configure_bonding_curve(
+        &mint,
+        &admin.pubkey(),
+        &reserve,
+        test_curve(),
+        1_450_000_000,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let trader = h.funded_wallet(LAMPORTS_PER_SOL).await;
+    let trader_ata = create_ata(&mut h.ctx, &mint, &trader.pubkey()).await;
+    let state = h.token_state().await;
+    let keys = instructions::CurveTradeAccounts::from_state(
+        mint,
+        trader.pubkey(),
+        trader_ata,
+        spl_token::id(),
+        &state,
+    );
+
+    // The SOL reserve can only be paid out to the admin
+    let stray = instructions::CurveTradeAccounts {
+        migration_payout: trader.pubkey(),
+        ..keys
+    };
+    let ix = instructions::buy_on_curve(&stray, 10_000, u64::MAX);
+    assert_error(h.send(&[ix], &[&trader]).await, ErrorCode::AccountMismatch);
+
+    let ix = instructions::buy_on_curve(&keys, 10_000, u64::MAX);
+    assert_error(
+        h.send(&[ix], &[&trader]).await,
+        ErrorCode::TradingNotEnabled,
+    );
+    h.warp(LAUNCH_GATE).await;
+
+    let lamports = h.account(&trader.pubkey()).await.lamports;
+    let ix = instructions::buy_on_curve(&keys, 10_000, u64::MAX);
+    h.send(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(
+        h.account(&trader.pubkey()).await.lamports,
+        lamports - 10_000_000
+    );
+    assert_eq!(h.balance(trader_ata).await, 9_000);
+
+    // 10_000 at 1_100 and 10_000 at 1_200
+    let ix = instructions::buy_on_curve(&keys, 20_000, 22_999_999);
+    assert_error(h.send(&[ix], &[&trader]).await, ErrorCode::SlippageExceeded);
+    let ix = instructions::buy_on_curve(&keys, 20_000, 23_000_000);
+    h.send(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(h.balance(trader_ata).await, 27_000);
+
+    // The 8_100 reaching the reserve sell back at 1_200
+    let lamports = h.account(&trader.pubkey()).await.lamports;
+    let ix = instructions::sell_on_curve(&keys, 9_000, 9_720_001);
+    assert_error(h.send(&[ix], &[&trader]).await, ErrorCode::SlippageExceeded);
+    let ix = instructions::sell_on_curve(&keys, 9_000, 9_720_000);
+    h.send(&[ix], &[&trader]).await.unwrap();
+    assert_eq!(
+        h.account(&trader.pubkey()).await.lamports,
+        lamports + 9_720_000
+    );
+    let curve = accounts::decode_bonding_curve(&h.account(&bonding_curve).await.data).unwrap();
+    assert_eq!(curve.tokens_sold, 21_900);
+    assert_eq!(curve.sol_reserve, 23_280_000);
+    assert!(!curve.migrated);
+
+    // Selling through 50_000 lifts the price to 1_500, past the threshold
+    let admin_lamports = h.account(&admin.pubkey()).await.lamports;
+    let ix = instructions::buy_on_curve(&keys, 28_100, 36_720_000);
+    h.send(&[ix], &[&trader]).await.unwrap();
+    let curve = accounts::decode_bonding_curve(&h.account(&bonding_curve).await.data).unwrap();
+    assert!(curve.migrated);
+    assert_eq!(curve.sol_reserve, 0);
+    assert_eq!(h.balance(reserve).await, 0);
+    assert_eq!(h.balance(lp_pool).await, 350_000);
+    assert_eq!(
+        h.account(&admin.pubkey()).await.lamports,
+        admin_lamports + 60_000_000
+    );
+    assert_eq!(h.token_state().await.liquidity_pool_balance, 350_000);
+
+    let ix = instructions::buy_on_curve(&keys, 1_000, u64::MAX);
+    assert_error(h.send(&[ix], &[&trader]).await, ErrorCode::CurveMigrated);
+}
+
+#[tokio::test]
+async fn referred_transfers_credit_the_referrer() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let referrer = Keypair::new();
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let referrer_ata = create_ata(&mut h.ctx, &mint, &referrer.pubkey()).await;
+
+    let stray = create_token_account(&mut h.ctx, &mint, &admin.pubkey()).await;
+    let ix = instructions::configure_referrals(&mint, &admin.pubkey(), &stray, 2_000);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::InvalidReferralConfig,
+    );
+    let vault = create_token_account(&mut h.ctx, &mint, &pda::mint_authority(&mint).0).await;
+    let ix = instructions::configure_referrals(&mint, &admin.pubkey(), &vault, 2_000);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let ix = instructions::register_referrer(&mint, &admin.pubkey(), &admin.pubkey());
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::SelfReferral);
+    let ix = instructions::register_referrer(&mint, &admin.pubkey(), &admin_ata);
+    assert_error(h.send(&[ix], &[&admin]).await, ErrorCode::InvalidReferrer);
+    let ix = instructions::register_referrer(&mint, &admin.pubkey(), &referrer.pubkey());
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    h.warp(LAUNCH_GATE).await;
+
+    let state = h.token_state().await;
+    let keys = h
+        .transfer_keys(admin_ata, receiver, admin.pubkey())
+        .with_referral(&admin.pubkey(), Some(&referrer.pubkey()), &state);
+
+    // A referred sender can't skip its referrer by leaving the referral accounts out
+    let unreferred = h.transfer_keys(admin_ata, receiver, admin.pubkey());
+    assert_error(
+        h.send(
+            &[instructions::transfer_tokens(&unreferred, 10_000)],
+            &[&admin],
+        )
+        .await,
+        ErrorCode::MissingReferralAccounts,
+    );
+    let partial = instructions::TransferTokensAccounts {
+        referral_vault: None,
+        ..keys
+    };
+    assert_error(
+        h.send(
+            &[instructions::transfer_tokens(&partial, 10_000)],
+            &[&admin],
+        )
+        .await,
+        ErrorCode::MissingReferralAccounts,
+    );
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+        .await
+        .unwrap();
+
+    // 20% of the 700 rewards share goes to the referrer
+    assert_eq!(h.balance(receiver).await, 9_000);
+    assert_eq!(h.balance(h.rewards_pool).await, 560);
+    assert_eq!(h.balance(vault).await, 140);
+    assert_eq!(h.balance(h.lp_fund).await, 200);
+    assert_eq!(h.supply().await, TOTAL_SUPPLY - 100);
+
+    let stats_key = pda::referrer_stats(&mint, &referrer.pubkey()).0;
+    let stats = accounts::decode_referrer_stats(&h.account(&stats_key).await.data).unwrap();
+    assert_eq!(stats.referred_count, 1);
+    assert_eq!(stats.referred_tax, 1_000);
+    assert_eq!(stats.total_earned, 140);
+    assert_eq!(stats.claimable, 140);
+
+    let claim = instructions::claim_referral_rewards(
+        &mint,
+        &referrer.pubkey(),
+        &referrer_ata,
+        &vault,
+        &spl_token::id(),
+    );
+    h.send(&[claim.clone()], &[&referrer]).await.unwrap();
+    assert_eq!(h.balance(referrer_ata).await, 140);
+    assert_eq!(h.balance(vault).await, 0);
+    assert_error(
+        h.send(&[claim], &[&referrer]).await,
+        ErrorCode::NothingToClaim,
+    );
+}
+
+#[tokio::test]
+async fn loyal_holders_pay_reduced_tax_until_their_balance_drops() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let holder = Keypair::new();
+    let holder_ata = create_ata(&mut h.ctx, &mint, &holder.pubkey()).await;
+    let record = pda::holder_record(&mint, &holder_ata).0;
+    let payer = h.ctx.payer.pubkey();
+
+    let tier = accounts::LoyaltyTier {
+        min_hold: 1_000,
+        min_balance: 100_000,
+        discount_bps: 5_000,
+    };
+    let invalid = accounts::LoyaltyTier {
+        discount_bps: 10_001,
+        ..tier
+    };
+    let ix = instructions::configure_loyalty_tiers(&mint, &admin.pubkey(), vec![invalid]);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::InvalidLoyaltyTiers,
+    );
+    let ix = instructions::configure_loyalty_tiers(&mint, &admin.pubkey(), vec![tier]);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.token_state().await.loyalty_min_balance, 100_000);
+
+    h.warp(LAUNCH_GATE).await;
+
+    // While loyalty is on, senders must pass their holder record
+    let mut keys = h.transfer_keys(admin_ata, holder_ata, admin.pubkey());
+    keys.receiver_record = Some(record);
+    keys.payer = Some(payer);
+    assert_error(
+        h.send(&[instructions::transfer_tokens(&keys, 200_000)], &[&admin])
+            .await,
+        ErrorCode::MissingHolderAccounts,
+    );
+    let ix = instructions::register_holder(&mint, &payer, &admin_ata);
+    h.send(&[ix], &[]).await.unwrap();
+    keys.sender_record = Some(pda::holder_record(&mint, &admin_ata).0);
+    h.send(&[instructions::transfer_tokens(&keys, 200_000)], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(holder_ata).await, 180_000);
+
+    h.warp(1_000).await;
+
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let mut keys = h.transfer_keys(holder_ata, receiver, holder.pubkey());
+    keys.sender_record = Some(record);
+    keys.loyalty_tiers = Some(pda::loyalty_tiers(&mint).0);
+
+    // Half of the 10% tax is waived after the full hold
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&holder])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(receiver).await, 9_500);
+
+    // The discount still applies, but the balance falls below the threshold
+    h.send(&[instructions::transfer_tokens(&keys, 80_000)], &[&holder])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(receiver).await, 85_500);
+    let holder_record = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(holder_record.hold_start, None);
+
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&holder])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(receiver).await, 94_500);
+}
+
+#[tokio::test]
+async fn holds_count_from_the_tier_setup_and_break_on_burns() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let holder = Keypair::new();
+    let holder_ata = create_ata(&mut h.ctx, &mint, &holder.pubkey()).await;
+    let record = pda::holder_record(&mint, &holder_ata).0;
+    h.warp(LAUNCH_GATE).await;
+
+    // The record opens, and its hold starts, before any tiers exist
+    let mut keys = h.transfer_keys(admin_ata, holder_ata, admin.pubkey());
+    keys.receiver_record = Some(record);
+    keys.payer = Some(h.ctx.payer.pubkey());
+    h.send(&[instructions::transfer_tokens(&keys, 200_000)], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(holder_ata).await, 180_000);
+
+    h.warp(2_000).await;
+    let tier = accounts::LoyaltyTier {
+        min_hold: 1_000,
+        min_balance: 100_000,
+        discount_bps: 5_000,
+    };
+    let ix = instructions::configure_loyalty_tiers(&mint, &admin.pubkey(), vec![tier]);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    let mut keys = h.transfer_keys(holder_ata, receiver, holder.pubkey());
+    keys.sender_record = Some(record);
+    keys.loyalty_tiers = Some(pda::loyalty_tiers(&mint).0);
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&holder])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(receiver).await, 9_000);
+
+    h.warp(1_000).await;
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&holder])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(receiver).await, 18_500);
+
+    // A burn below the lowest tier breaks the hold like a transfer would
+    let burn = instructions::user_burn(
+        &mint,
+        &holder.pubkey(),
+        &holder_ata,
+        &h.token_program,
+        None,
+        70_000,
+    );
+    let mut skipped = burn.clone();
+    skipped.accounts[8] = AccountMeta::new_readonly(my_solami_token::ID, false);
+    assert_error(
+        h.send(&[skipped], &[&holder]).await,
+        ErrorCode::MissingHolderAccounts,
+    );
+    h.send(&[burn], &[&holder]).await.unwrap();
+    assert_eq!(h.balance(holder_ata).await, 90_000);
+    let holder_record = accounts::decode_holder_record(&h.account(&record).await.data).unwrap();
+    assert_eq!(holder_record.hold_start, None);
+}
+
+#[tokio::test]
+async fn passed_proposals_execute_through_the_governance_pda() {
+    let mut h = hook_harness().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let (voter, voter_ata) = (h.whitelisted.insecure_clone(), h.whitelisted_ata);
+    let governance = pda::governance(&mint).0;
+    let newcomer = Keypair::new().pubkey();
+    let payer = h.ctx.payer.pubkey();
+    let ix = system_instruction::transfer(&payer, &voter.pubkey(), LAMPORTS_PER_SOL);
+    h.send(&[ix], &[]).await.unwrap();
+
+    let ix = instructions::configure_governance(&mint, &admin.pubkey(), 100, 500_000, 200_000);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    for account in [admin_ata, voter_ata] {
+        let ix = instructions::register_checkpoints(&mint, &payer, &account);
+        h.send(&[ix], &[]).await.unwrap();
+    }
+    let ix = instructions::transfer_ownership(&mint, &admin.pubkey(), governance);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let whitelist = accounts::ProposalAction::AddToWhitelist { wallet: newcomer };
+    let ix = instructions::create_proposal(&mint, &voter.pubkey(), &voter_ata, 0, 1, whitelist);
+    assert_error(
+        h.send(&[ix], &[&voter]).await,
+        ErrorCode::InsufficientVotingPower,
+    );
+    let freeze = accounts::ProposalAction::FreezeContract { freeze: true };
+    for (id, action) in [(0, whitelist), (1, freeze)] {
+        let ix =
+            instructions::create_proposal(&mint, &admin.pubkey(), &admin_ata, id, id + 1, action);
+        h.send(&[ix], &[&admin]).await.unwrap();
+    }
+    let proposal =
+        accounts::decode_proposal(&h.account(&pda::proposal(&mint, 0).0).await.data).unwrap();
+    assert_eq!(proposal.snapshot_id, 1);
+    assert_eq!(h.token_state().await.snapshot_id, 2);
+
+    // Tokens moved after the snapshot still vote with the account that held them
+    let mut keys = h
+        .transfer_keys(admin_ata, voter_ata, admin.pubkey())
+        .with_checkpoints();
+    keys.payer = Some(payer);
+    h.send(&[instructions::transfer_tokens(&keys, 100_000)], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(voter_ata).await, 250_000);
+
+    // Trading is open, which locks the admin's own launch tax instruction
+    let launch_tax = accounts::ProposalAction::ConfigureLaunchTax {
+        start_bps: 2_000,
+        window: 600,
+        steps: 2,
+    };
+    let ix = instructions::create_proposal(&mint, &admin.pubkey(), &admin_ata, 2, 3, launch_tax);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    for (owner, account, id, approve) in [
+        (&admin, admin_ata, 0, true),
+        (&voter, voter_ata, 0, true),
+        (&admin, admin_ata, 1, false),
+        (&voter, voter_ata, 1, true),
+        (&admin, admin_ata, 2, true),
+        (&voter, voter_ata, 2, true),
+    ] {
+        let ix = instructions::cast_vote(&mint, &owner.pubkey(), &account, id, approve);
+        h.send(&[ix], &[owner]).await.unwrap();
+    }
+    let ix = instructions::cast_vote(&mint, &admin.pubkey(), &admin_ata, 0, false);
+    assert!(h.send(&[ix], &[&admin]).await.is_err());
+    let proposal =
+        accounts::decode_proposal(&h.account(&pda::proposal(&mint, 0).0).await.data).unwrap();
+    assert_eq!((proposal.yes_votes, proposal.no_votes), (600_000, 0));
+    let vote_record = pda::vote_record(&pda::proposal(&mint, 0).0, &voter_ata).0;
+    let vote = accounts::decode_vote_record(&h.account(&vote_record).await.data).unwrap();
+    assert_eq!((vote.voter, vote.weight), (voter.pubkey(), 150_000));
+
+    let state = h.token_state().await;
+    let execute_whitelist = instructions::execute_proposal(&mint, 0, &whitelist, &state);
+    assert_error(
+        h.send(&[execute_whitelist.clone()], &[]).await,
+        ErrorCode::ProposalNotPassed,
+    );
+
+    let ix = instructions::cast_vote(&mint, &voter.pubkey(), &admin_ata, 0, true);
+    assert_error(
+        h.send(&[ix], &[&voter]).await,
+        anchor_lang::error::ErrorCode::ConstraintTokenOwner,
+    );
+
+    h.warp(100).await;
+    let ix = instructions::execute_proposal(&mint, 1, &freeze, &state);
+    assert_error(h.send(&[ix], &[]).await, ErrorCode::ProposalNotPassed);
+    h.send(&[execute_whitelist.clone()], &[]).await.unwrap();
+    let whitelist_key = pda::whitelist(&mint).0;
+    let wallets = accounts::decode_whitelist(&h.account(&whitelist_key).await.data)
+        .unwrap()
+        .wallets;
+    assert!(wallets.contains(&newcomer));
+    assert_error(
+        h.send(&[execute_whitelist], &[]).await,
+        ErrorCode::ProposalExecuted,
+    );
+
+    let ix = instructions::execute_proposal(&mint, 2, &launch_tax, &state);
+    h.send(&[ix], &[]).await.unwrap();
+    let state = h.token_state().await;
+    assert_eq!(
+        (
+            state.launch_tax_bps,
+            state.launch_tax_window,
+            state.launch_tax_steps
+        ),
+        (2_000, 600, 2)
+    );
+
+    // The old admin key no longer passes admin checks
+    let ix = instructions::freeze_contract(&mint, &admin.pubkey(), true);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        anchor_lang::error::ErrorCode::ConstraintAddress,
+    );
+}
+
+#[tokio::test]
+async fn direct_transfers_after_a_proposal_cannot_vote_again() {
+    let mut classic = Harness::new().await;
+    let (mint, admin) = (classic.mint, classic.admin.insecure_clone());
+    let ix = instructions::configure_governance(&mint, &admin.pubkey(), 100, 1, 0);
+    assert_error(
+        classic.send(&[ix], &[&admin]).await,
+        ErrorCode::GovernanceNeedsTransferHook,
+    );
+
+    let mut h = hook_harness().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let (voter, voter_ata) = (h.whitelisted.insecure_clone(), h.whitelisted_ata);
+    let second_ata = create_token_account(&mut h.ctx, &mint, &voter.pubkey()).await;
+    let payer = h.ctx.payer.pubkey();
+    let ix = system_instruction::transfer(&payer, &voter.pubkey(), LAMPORTS_PER_SOL);
+    h.send(&[ix], &[]).await.unwrap();
+
+    let ix = instructions::configure_governance(&mint, &admin.pubkey(), 100, 1, 0);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let ix = instructions::register_checkpoints(&mint, &payer, &voter_ata);
+    h.send(&[ix], &[]).await.unwrap();
+    let action = accounts::ProposalAction::FreezeContract { freeze: true };
+    let ix = instructions::create_proposal(&mint, &admin.pubkey(), &admin_ata, 0, 1, action);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    let ix = instructions::cast_vote(&mint, &voter.pubkey(), &voter_ata, 0, true);
+    h.send(&[ix], &[&voter]).await.unwrap();
+
+    // The hook checkpoints the sender, so the moved tokens still count where they were
+    h.direct_transfer_from(voter_ata, &voter, second_ata, 100_000)
+        .await
+        .unwrap();
+    assert_eq!(h.balance(second_ata).await, 100_000);
+    assert_eq!(h.balance_at(voter_ata, 1).await, 150_000);
+
+    // An account without checkpoints, or with ones opened after the snapshot,
+    // can't prove what it held
+    let vote = instructions::cast_vote(&mint, &voter.pubkey(), &second_ata, 0, true);
+    assert_error(
+        h.send(&[vote.clone()], &[&voter]).await,
+        ErrorCode::BalanceNotProvable,
+    );
+    let ix = instructions::register_checkpoints(&mint, &payer, &second_ata);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_error(
+        h.send(&[vote], &[&voter]).await,
+        ErrorCode::BalanceNotProvable,
+    );
+    let ix = instructions::cast_vote(&mint, &admin.pubkey(), &admin_ata, 0, true);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::BalanceNotProvable,
+    );
+
+    let proposal =
+        accounts::decode_proposal(&h.account(&pda::proposal(&mint, 0).0).await.data).unwrap();
+    assert_eq!((proposal.yes_votes, proposal.no_votes), (150_000, 0));
+}
+
+#[tokio::test]
+async fn checkpoints_keep_balances_as_of_each_snapshot() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let whitelisted_ata = h.whitelisted_ata;
+    let receiver = create_ata(&mut h.ctx, &mint, &Keypair::new().pubkey()).await;
+    h.warp(LAUNCH_GATE).await;
+
+    // Nothing to checkpoint before the first snapshot
+    h.transfer(receiver, 10_000).await.unwrap();
+    let ix = instructions::take_snapshot(&mint, &admin.pubkey(), 1);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let snapshot =
+        accounts::decode_snapshot(&h.account(&pda::snapshot(&mint, 1).0).await.data).unwrap();
+    assert_eq!(snapshot.id, 1);
+    assert_eq!(h.token_state().await.snapshot_id, 1);
+
+    assert_error(
+        h.transfer(receiver, 10_000).await,
+        ErrorCode::MissingCheckpointAccounts,
+    );
+    let mut keys = h
+        .transfer_keys(admin_ata, receiver, admin.pubkey())
+        .with_checkpoints();
+    assert_error(
+        h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+            .await,
+        ErrorCode::MissingCheckpointAccounts,
+    );
+    keys.payer = Some(h.ctx.payer.pubkey());
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+        .await
+        .unwrap();
+
+    let ix = instructions::take_snapshot(&mint, &admin.pubkey(), 2);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    h.send(&[instructions::transfer_tokens(&keys, 10_000)], &[&admin])
+        .await
+        .unwrap();
+    assert_eq!(h.balance(receiver).await, 27_000);
+
+    assert_eq!(h.balance_at(receiver, 1).await, 9_000);
+    assert_eq!(h.balance_at(receiver, 2).await, 18_000);
+    assert_eq!(h.balance_at(admin_ata, 1).await, 440_000);
+    assert_eq!(h.balance_at(admin_ata, 2).await, 430_000);
+    // Accounts that haven't moved since hold their live balance
+    assert_eq!(h.balance_at(whitelisted_ata, 1).await, 150_000);
+
+    // Pools move without checkpoints, so snapshots don't cover them
+    let ix = instructions::balance_at(&mint, &h.rewards_pool, 1);
+    assert_error(h.send(&[ix], &[]).await, ErrorCode::PoolNotSnapshotted);
+    let ix = instructions::register_checkpoints(&mint, &h.ctx.payer.pubkey(), &h.lp_pool);
+    assert_error(h.send(&[ix], &[]).await, ErrorCode::PoolNotSnapshotted);
+}
+
+#[tokio::test]
+async fn burns_after_a_snapshot_keep_the_pre_burn_balance() {
+    let mut h = Harness::new().await;
+    let (mint, admin_ata, admin) = (h.mint, h.admin_ata, h.admin.insecure_clone());
+    let ix = instructions::take_snapshot(&mint, &admin.pubkey(), 1);
+    h.send(&[ix], &[&admin]).await.unwrap();
+
+    // Skipping the checkpoints would let the burn rewrite the snapshot
+    let mut ix = instructions::user_burn(
+        &mint,
+        &admin.pubkey(),
+        &admin_ata,
+        &h.token_program,
+        None,
+        50_000,
+    );
+    ix.accounts[5] = AccountMeta::new_readonly(my_solami_token::ID, false);
+    assert_error(
+        h.send(&[ix], &[&admin]).await,
+        ErrorCode::MissingCheckpointAccounts,
+    );
+
+    let ix = instructions::user_burn(
+        &mint,
+        &admin.pubkey(),
+        &admin_ata,
+        &h.token_program,
+        None,
+        50_000,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.balance(admin_ata).await, 400_000);
+    assert_eq!(h.balance_at(admin_ata, 1).await, 450_000);
+
+    let ix = instructions::take_snapshot(&mint, &admin.pubkey(), 2);
+    h.send(&[ix], &[&admin]).await.unwrap();
+    let ix = instructions::manual_burn(
+        &mint,
+        &admin.pubkey(),
+        &admin_ata,
+        &h.token_program,
+        None,
+        100_000,
+    );
+    h.send(&[ix], &[&admin]).await.unwrap();
+    assert_eq!(h.balance(admin_ata).await, 300_000);
+    assert_eq!(h.balance_at(admin_ata, 1).await, 450_000);
+    assert_eq!(h.balance_at(admin_ata, 2).await, 400_000);
+}
@@ -0,0 +1,233 @@
+//! Property tests for the tax and distribution arithmetic.
+
+use my_solami_token::state::{
+    BalanceCheckpoints, CurveKind, CurveShape, LoyaltyTier, MAX_CHECKPOINTS,
+};
+use my_solami_token::tokenomics::{
+    curve_buy_cost, curve_sell_proceeds, discounted_tax_bps, initial_allocation, launch_tax_bps,
+    loyalty_discount_bps, presale_tokens, split_tax, split_tax_with_referral, transfer_amounts,
+    transfer_amounts_at, BASE_TAX_BPS, TAX_PERCENT,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn net_plus_tax_is_amount(amount: u64, whitelisted: bool) {
+        let (net, tax) = transfer_amounts(amount, whitelisted);
+        prop_assert_eq!(net as u128 + tax as u128, amount as u128);
+    }
+
+    #[test]
+    fn whitelisted_transfers_are_untaxed(amount: u64) {
+        prop_assert_eq!(transfer_amounts(amount, true), (amount, 0));
+    }
+
+    #[test]
+    fn tax_never_exceeds_rate(amount: u64) {
+        let (_, tax) = transfer_amounts(amount, false);
+        prop_assert!(tax as u128 * 100 <= amount as u128 * TAX_PERCENT as u128);
+        prop_assert!(amount as u128 * TAX_PERCENT as u128 - tax as u128 * 100 < 100);
+    }
+
+    #[test]
+    fn split_sums_to_tax(tax: u64) {
+        let split = split_tax(tax);
+        prop_assert_eq!(
+            split.rewards as u128 + split.lp_fund as u128 + split.burn as u128,
+            tax as u128
+        );
+        prop_assert!(split.rewards >= split.lp_fund);
+        // Only rounding dust is added to the 10% burn share
+        prop_assert!(split.burn as u128 * 10 <= tax as u128 + 20);
+    }
+
+    #[test]
+    fn referral_comes_out_of_the_rewards_share(tax: u64, referral_bps in 0u16..=10_000) {
+        let split = split_tax(tax);
+        let referred = split_tax_with_referral(tax, referral_bps);
+        prop_assert_eq!(referred.rewards + referred.referral, split.rewards);
+        prop_assert_eq!((referred.lp_fund, referred.burn), (split.lp_fund, split.burn));
+    }
+
+    #[test]
+    fn loyalty_discount_never_raises_tax(tax_bps in 0u16..=10_000, discount_bps: u16) {
+        let discounted = discounted_tax_bps(tax_bps, discount_bps);
+        prop_assert!(discounted <= tax_bps);
+        prop_assert_eq!(discounted_tax_bps(tax_bps, 0), tax_bps);
+    }
+
+    #[test]
+    fn loyalty_discount_grows_with_hold(
+        tiers in prop::collection::vec(loyalty_tier(), 0..=4),
+        balance: u64,
+        held_for in 0i64..1_000_000,
+        longer in 0i64..1_000_000,
+    ) {
+        prop_assert!(
+            loyalty_discount_bps(&tiers, balance, held_for)
+                <= loyalty_discount_bps(&tiers, balance, held_for + longer)
+        );
+    }
+
+    #[test]
+    fn allocation_never_exceeds_supply(total_supply in 1u64.., wallets in 1usize..=15) {
+        let allocation = initial_allocation(total_supply, wallets).unwrap();
+        let minted = allocation.minted(wallets as u64);
+        prop_assert!(minted <= total_supply);
+        // Each share floors by less than one unit, plus per-wallet dust
+        prop_assert!(total_supply - minted < 4 + wallets as u64);
+    }
+
+    #[test]
+    fn presale_tranche_keeps_minted_supply(
+        total_supply in 1u64..,
+        wallets in 1usize..=15,
+        tranche: u64,
+    ) {
+        let allocation = initial_allocation(total_supply, wallets).unwrap();
+        match allocation.with_presale(tranche) {
+            Ok(with_presale) => {
+                prop_assert_eq!(with_presale.presale, tranche);
+                prop_assert_eq!(
+                    with_presale.minted(wallets as u64),
+                    allocation.minted(wallets as u64)
+                );
+            }
+            Err(_) => prop_assert!(tranche > allocation.marketing),
+        }
+    }
+
+    #[test]
+    fn presale_receipts_never_exceed_the_total_sold(a: u32, b: u32, tokens_per_sol: u32) {
+        let (a, b, tokens_per_sol) = (a as u64, b as u64, tokens_per_sol as u64);
+        let claimed = presale_tokens(a, tokens_per_sol).unwrap()
+            + presale_tokens(b, tokens_per_sol).unwrap();
+        prop_assert!(claimed <= presale_tokens(a + b, tokens_per_sol).unwrap());
+    }
+
+    #[test]
+    fn taxed_amounts_sum_at_any_rate(amount: u64, tax_bps in 0u16..=10_000) {
+        let (net, tax) = transfer_amounts_at(amount, tax_bps);
+        prop_assert_eq!(net as u128 + tax as u128, amount as u128);
+    }
+
+    #[test]
+    fn launch_tax_stays_between_base_and_start(
+        start_bps in BASE_TAX_BPS..=10_000,
+        window in 1i64..1_000_000,
+        steps: u8,
+        elapsed in -1_000i64..2_000_000,
+    ) {
+        let rate = launch_tax_bps(start_bps, window, steps, elapsed);
+        prop_assert!((BASE_TAX_BPS..=start_bps).contains(&rate));
+        if elapsed <= 0 {
+            prop_assert_eq!(rate, start_bps);
+        }
+        if elapsed >= window {
+            prop_assert_eq!(rate, BASE_TAX_BPS);
+        }
+    }
+
+    #[test]
+    fn launch_tax_never_increases(
+        start_bps in BASE_TAX_BPS..=10_000,
+        window in 1i64..1_000_000,
+        steps: u8,
+        elapsed in 0i64..1_000_000,
+        later in 0i64..1_000_000,
+    ) {
+        prop_assert!(
+            launch_tax_bps(start_bps, window, steps, elapsed + later)
+                <= launch_tax_bps(start_bps, window, steps, elapsed)
+        );
+    }
+
+    #[test]
+    fn curve_round_trip_never_pays_out_more(
+        shape in curve_shape(),
+        sold in 0u64..1_000_000,
+        amount in 1u64..1_000_000,
+    ) {
+        if let Some(cost) = curve_buy_cost(&shape, sold, amount) {
+            let proceeds = curve_sell_proceeds(&shape, sold + amount, amount).unwrap();
+            prop_assert!(proceeds <= cost);
+        }
+    }
+
+    #[test]
+    fn curve_cost_never_falls_as_supply_is_sold(
+        shape in curve_shape(),
+        sold in 0u64..1_000_000,
+        later in 0u64..1_000_000,
+        amount in 1u64..100_000,
+    ) {
+        if let Some(later_cost) = curve_buy_cost(&shape, sold + later, amount) {
+            prop_assert!(curve_buy_cost(&shape, sold, amount).unwrap() <= later_cost);
+        }
+    }
+
+    #[test]
+    fn checkpoints_match_the_balance_history(
+        ops in prop::collection::vec(prop::option::of(any::<u64>()), 0..120),
+    ) {
+        // `None` takes a snapshot, `Some` changes the balance through a transfer
+        let mut checkpoints = BalanceCheckpoints {
+            token_account: Default::default(),
+            checkpoints: Vec::new(),
+            pruned_through: 0,
+            opened_at: 0,
+            bump: 0,
+        };
+        let mut balance = 0u64;
+        let mut history = Vec::new();
+        for op in ops {
+            match op {
+                None => history.push(balance),
+                Some(next) => {
+                    checkpoints.record(history.len() as u64, balance);
+                    balance = next;
+                }
+            }
+        }
+
+        prop_assert!(checkpoints.checkpoints.len() <= MAX_CHECKPOINTS);
+        for (index, expected) in history.iter().enumerate() {
+            let id = index as u64 + 1;
+            match checkpoints.balance_at(id, balance) {
+                Some(actual) => prop_assert_eq!(actual, *expected),
+                None => prop_assert!(checkpoints.pruned_through >= id),
+            }
+        }
+    }
+}
+
+fn loyalty_tier() -> impl Strategy<Value = LoyaltyTier> {
+    (0i64..1_000_000, 1u64.., 0u16..=10_000).prop_map(|(min_hold, min_balance, discount_bps)| {
+        LoyaltyTier {
+            min_hold,
+            min_balance,
+            discount_bps,
+        }
+    })
+}
+
+fn curve_shape() -> impl Strategy<Value = CurveShape> {
+    (
+        prop_oneof![Just(CurveKind::Linear), Just(CurveKind::Exponential)],
+        1u64..1_000_000_000_000,
+        0u64..10_000,
+        10_000u64..1_000_000,
+    )
+        .prop_map(|(kind, start_price, growth, step_size)| CurveShape {
+            kind,
+            start_price,
+            growth,
+            step_size,
+        })
+}
+
+#[test]
+fn allocation_rejects_empty_inputs() {
+    assert!(initial_allocation(0, 1).is_err());
+    assert!(initial_allocation(1, 0).is_err());
+}
@@ -2,7 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { MySolamiToken } from "../target/types/my_solami_token";
 import { Keypair, PublicKey } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getMint, getOrCreateAssociatedTokenAccount, mintTo } from "@solana/spl-token";
 import assert from "assert";
 
 describe("my_solami_token", () => {
@@ -120,7 +120,7 @@ describe("my_solami_token", () => {
     const whitelistWallets = [whitelistWallet1.publicKey, whitelistWallet2.publicKey];
 
     const tx = await program.methods
-      .initializeToken(totalSupply, whitelistWallets)
+      .initializeToken(totalSupply, whitelistWallets, null, new anchor.BN(0))
       .accounts({
         tokenState: tokenState,
         whitelist: whitelist,
@@ -131,6 +131,10 @@ describe("my_solami_token", () => {
         lpPool: lpPool,
         rewardsPool: rewardsPool,
         lpFund: lpFund,
+        stakingPool: lpFund,
+        swapWallet: adminTokenAccount,
+        presaleEscrow: null,
+        curveReserve: null,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
@@ -146,6 +150,8 @@ describe("my_solami_token", () => {
     assert.strictEqual(tokenStateAccount.admin.toString(), admin.publicKey.toString());
     assert.strictEqual(tokenStateAccount.totalTransactions, 0);
     assert.strictEqual(tokenStateAccount.totalTaxCollected, 0);
+    assert.strictEqual(tokenStateAccount.rewardsPool.toString(), rewardsPool.toString());
+    assert.strictEqual(tokenStateAccount.swapWallet.toString(), adminTokenAccount.toString());
     assert.strictEqual(tokenStateAccount.totalBurned, 0);
     assert.strictEqual(tokenStateAccount.isFrozen, false);
 
@@ -228,7 +234,7 @@ describe("my_solami_token", () => {
         receiver: user1TokenAccount,
         rewardsPool: rewardsPool,
         lpFund: lpFund,
-        senderAuthority: admin.publicKey, // Use the admin Keypair's publicKey
+        authority: admin.publicKey, // Use the admin Keypair's publicKey
         mint: mint,
         whitelist: whitelist,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -264,7 +270,7 @@ describe("my_solami_token", () => {
         receiver: user2TokenAccount,
         rewardsPool: rewardsPool,
         lpFund: lpFund,
-        senderAuthority: user1.publicKey, // Use the user1 Keypair's publicKey
+        authority: user1.publicKey, // Use the user1 Keypair's publicKey
         mint: mint,
         whitelist: whitelist,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -299,6 +305,7 @@ describe("my_solami_token", () => {
       .addToWhitelist(newWhitelistWallet.publicKey)
       .accounts({
         tokenState: tokenState,
+        mint: mint,
         whitelist: whitelist,
         admin: admin.publicKey, // Use the admin Keypair's publicKey
       })
@@ -315,6 +322,7 @@ describe("my_solami_token", () => {
       .removeFromWhitelist(newWhitelistWallet.publicKey)
       .accounts({
         tokenState: tokenState,
+        mint: mint,
         whitelist: whitelist,
         admin: admin.publicKey, // Use the admin Keypair's publicKey
       })
@@ -394,6 +402,7 @@ describe("my_solami_token", () => {
       .transferOwnership(newAdmin.publicKey)
       .accounts({
         tokenState: tokenState,
+        mint: mint,
         admin: admin.publicKey, // Use the admin Keypair's publicKey
       })
       .signers([admin]) // Use the admin Keypair
@@ -410,6 +419,7 @@ describe("my_solami_token", () => {
       .freezeContract(true)
       .accounts({
         tokenState: tokenState,
+        mint: mint,
         admin: admin.publicKey, // Use the admin Keypair's publicKey
       })
       .signers([admin]) // Use the admin Keypair
@@ -429,7 +439,7 @@ describe("my_solami_token", () => {
           receiver: user1.publicKey,
           rewardsPool: rewardsPool,
           lpFund: lpFund,
-          senderAuthority: admin.publicKey, // Use the admin Keypair's publicKey
+          authority: admin.publicKey, // Use the admin Keypair's publicKey
           mint: mint,
           whitelist: whitelist,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -445,6 +455,7 @@ describe("my_solami_token", () => {
       .freezeContract(false)
       .accounts({
         tokenState: tokenState,
+        mint: mint,
         admin: admin.publicKey, // Use the admin Keypair's publicKey
       })
       .signers([admin]) // Use the admin Keypair
@@ -463,7 +474,7 @@ describe("my_solami_token", () => {
         receiver: user1.publicKey,
         rewardsPool: rewardsPool,
         lpFund: lpFund,
-        senderAuthority: admin.publicKey, // Use the admin Keypair's publicKey
+        authority: admin.publicKey, // Use the admin Keypair's publicKey
         mint: mint,
         whitelist: whitelist,
         tokenProgram: TOKEN_PROGRAM_ID,
@@ -478,4 +489,102 @@ describe("my_solami_token", () => {
     const user1TokenBalance = await provider.connection.getTokenAccountBalance(user1.publicKey);
     assert.strictEqual(user1TokenBalance.value.uiAmount, 60000); // 50000 + 10000
   });
+
+  it("Emits a capped amount per epoch", async () => {
+    const [mintAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), mint.toBuffer()],
+      program.programId
+    );
+    const supplyBefore = (await getMint(provider.connection, mint)).supply;
+
+    await program.methods
+      .configureEmission(new anchor.BN((supplyBefore + BigInt(1000)).toString()), new anchor.BN(600), new anchor.BN(1))
+      .accounts({
+        tokenState: tokenState,
+        admin: admin.publicKey,
+        mint: mint,
+        emissionRecipient: rewardsPool,
+      })
+      .signers([admin])
+      .rpc();
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    const emitEpoch = () =>
+      program.methods
+        .emitEpoch()
+        .accounts({
+          tokenState: tokenState,
+          mintAuthority: mintAuthority,
+          mint: mint,
+          emissionRecipient: rewardsPool,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    // First epoch mints the full per-epoch amount, the second is capped by max supply
+    await emitEpoch();
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await emitEpoch();
+
+    const supplyAfter = (await getMint(provider.connection, mint)).supply;
+    assert.strictEqual(supplyAfter - supplyBefore, BigInt(1000));
+
+    const tokenStateAccount = await program.account.tokenState.fetch(tokenState);
+    assert.strictEqual(tokenStateAccount.totalEmitted.toNumber(), 1000);
+
+    // Max supply reached
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    try {
+      await emitEpoch();
+      assert.fail("expected MaxSupplyReached");
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "MaxSupplyReached");
+    }
+  });
+
+  it("Finalizes the supply", async () => {
+    const [mintAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority"), mint.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .finalizeSupply(true)
+      .accounts({
+        tokenState: tokenState,
+        admin: admin.publicKey,
+        mintAuthority: mintAuthority,
+        mint: mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([admin])
+      .rpc();
+
+    // Verify token state
+    const tokenStateAccount = await program.account.tokenState.fetch(tokenState);
+    assert.strictEqual(tokenStateAccount.supplyFinalized, true);
+
+    // Verify mint authority is revoked
+    const mintAccount = await getMint(provider.connection, mint);
+    assert.strictEqual(mintAccount.mintAuthority, null);
+
+    // Finalizing twice should fail
+    try {
+      await program.methods
+        .finalizeSupply(false)
+        .accounts({
+          tokenState: tokenState,
+          admin: admin.publicKey,
+          mintAuthority: mintAuthority,
+          mint: mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([admin])
+        .rpc();
+      assert.fail("finalizeSupply should fail once supply is finalized");
+    } catch (err) {
+      assert.strictEqual(err.error.errorCode.code, "SupplyFinalized");
+    }
+  });
 });
\ No newline at end of file